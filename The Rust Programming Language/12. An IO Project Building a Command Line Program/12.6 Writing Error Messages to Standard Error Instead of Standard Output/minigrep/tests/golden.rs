@@ -0,0 +1,137 @@
+//! Runs the `minigrep` binary for every case directory under `tests/golden`
+//! and compares what it printed with the expected files next to it.
+//!
+//! A case directory holds:
+//!
+//! * `args` - the command line arguments, one per line
+//! * `env` - optional `KEY=VALUE` lines added to the environment
+//! * `stdout`, `stderr` and `status` - the expected output and exit code
+//!
+//! The binary runs from the crate root, so `poem.txt` can be used directly.
+//! The wording of OS errors depends on the platform and locale, so only
+//! their code is compared: `... file or directory (os error 2)` is expected
+//! as `(os error 2)`.
+//! When output changes on purpose, regenerate the expected files with
+//! `BLESS=1 cargo test --test golden`.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn read_lines(path: &Path) -> Vec<String> {
+    match fs::read_to_string(path) {
+        Ok(contents) => contents.lines().map(String::from).collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// Drops the OS's description of an error, keeping the `(os error N)` code.
+fn normalize(output: &str) -> String {
+    output
+        .split_inclusive('\n')
+        .map(|line| match line.find("(os error ") {
+            Some(code) => match line[..code].rfind(": ") {
+                Some(start) => format!("{}{}", &line[..start + 2], &line[code..]),
+                None => line.to_string(),
+            },
+            None => line.to_string(),
+        })
+        .collect()
+}
+
+fn cases(root: &Path) -> Vec<PathBuf> {
+    let mut cases: Vec<PathBuf> = fs::read_dir(root.join("tests/golden"))
+        .expect("tests/golden should exist")
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.is_dir())
+        .collect();
+    cases.sort();
+    cases
+}
+
+fn run_case(root: &Path, case: &Path, bless: bool) -> Vec<String> {
+    let args = read_lines(&case.join("args"));
+    let mut command = Command::new(env!("CARGO_BIN_EXE_minigrep"));
+    command
+        .args(&args)
+        .current_dir(root)
+        .env_remove("IGNORE_CASE");
+
+    for line in read_lines(&case.join("env")) {
+        if let Some((key, value)) = line.split_once('=') {
+            command.env(key, value);
+        }
+    }
+
+    let output = command.output().expect("failed to run minigrep");
+    let status = match output.status.code() {
+        Some(code) => format!("{code}\n"),
+        None => String::from("signal\n"),
+    };
+
+    let actual = [
+        (
+            "stdout",
+            String::from_utf8_lossy(&output.stdout).into_owned(),
+        ),
+        (
+            "stderr",
+            normalize(&String::from_utf8_lossy(&output.stderr)),
+        ),
+        ("status", status),
+    ];
+
+    let name = case.file_name().unwrap().to_string_lossy();
+    let mut failures = Vec::new();
+
+    for (file, actual) in actual {
+        let path = case.join(file);
+
+        if bless {
+            fs::write(&path, &actual).unwrap();
+            continue;
+        }
+
+        match fs::read_to_string(&path) {
+            Ok(expected) if expected == actual => {}
+            Ok(expected) => failures.push(format!(
+                "{name}/{file} differs\n--- expected\n{expected}--- actual\n{actual}"
+            )),
+            Err(_) => failures.push(format!(
+                "{name}/{file} is missing, run with BLESS=1 to create it"
+            )),
+        }
+    }
+
+    failures
+}
+
+#[test]
+fn golden() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let bless = env::var_os("BLESS").is_some();
+
+    let failures: Vec<String> = cases(root)
+        .iter()
+        .flat_map(|case| run_case(root, case, bless))
+        .collect();
+
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}
+
+/// `output.txt` is what the book's `cargo run -- to poem.txt > output.txt`
+/// writes, so it has to match a fresh run.
+#[test]
+fn output_txt_is_current() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let output = Command::new(env!("CARGO_BIN_EXE_minigrep"))
+        .args(["to", "poem.txt"])
+        .current_dir(root)
+        .env_remove("IGNORE_CASE")
+        .output()
+        .expect("failed to run minigrep");
+
+    let expected = fs::read_to_string(root.join("output.txt")).unwrap();
+    assert_eq!(expected, String::from_utf8_lossy(&output.stdout));
+}
@@ -0,0 +1,448 @@
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::io::{self, Write};
+use std::iter;
+use std::process::Command;
+
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::{cursor, execute, terminal};
+
+use crate::{fold_case, Config};
+
+const PAGE: usize = 10;
+
+/// A key press, independent of the terminal backend so the browser can be
+/// driven from tests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Char(char),
+    Backspace,
+    Up,
+    Down,
+    PageUp,
+    PageDown,
+    Home,
+    End,
+    ToggleCase,
+    Enter,
+    Open,
+    Esc,
+}
+
+impl Key {
+    pub fn from_event(event: KeyEvent) -> Option<Key> {
+        if event.kind != KeyEventKind::Press {
+            return None;
+        }
+
+        if event.modifiers.contains(KeyModifiers::CONTROL) {
+            return match event.code {
+                KeyCode::Char('c') => Some(Key::Esc),
+                KeyCode::Char('o') => Some(Key::Open),
+                KeyCode::Char('n') => Some(Key::Down),
+                KeyCode::Char('p') => Some(Key::Up),
+                _ => None,
+            };
+        }
+
+        match event.code {
+            KeyCode::Char(c) => Some(Key::Char(c)),
+            KeyCode::Backspace => Some(Key::Backspace),
+            KeyCode::Up => Some(Key::Up),
+            KeyCode::Down => Some(Key::Down),
+            KeyCode::PageUp => Some(Key::PageUp),
+            KeyCode::PageDown => Some(Key::PageDown),
+            KeyCode::Home => Some(Key::Home),
+            KeyCode::End => Some(Key::End),
+            KeyCode::Tab => Some(Key::ToggleCase),
+            KeyCode::Enter => Some(Key::Enter),
+            KeyCode::Esc => Some(Key::Esc),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hit {
+    pub line_number: usize,
+    pub line: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    Print(Hit),
+    Open(Hit),
+    Quit,
+}
+
+pub struct Browser {
+    contents: String,
+    line_starts: Vec<usize>,
+    query: String,
+    ignore_case: bool,
+    hits: Vec<usize>,
+    selected: usize,
+}
+
+impl Browser {
+    pub fn new(contents: String, query: String, ignore_case: bool) -> Browser {
+        // Where each of `contents.lines()` starts: the start of the file and
+        // every position after a newline, except the very end.
+        let line_starts = iter::once(0)
+            .chain(contents.match_indices('\n').map(|(end, _)| end + 1))
+            .filter(|&start| start < contents.len())
+            .collect();
+
+        let mut browser = Browser {
+            contents,
+            line_starts,
+            query,
+            ignore_case,
+            hits: Vec::new(),
+            selected: 0,
+        };
+        browser.refresh();
+        browser
+    }
+
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    pub fn ignore_case(&self) -> bool {
+        self.ignore_case
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// The matching lines for the current query, in file order.
+    pub fn hits(&self) -> Vec<&str> {
+        self.hits.iter().map(|&index| self.line(index)).collect()
+    }
+
+    pub fn selected_hit(&self) -> Option<Hit> {
+        self.hits.get(self.selected).map(|&index| Hit {
+            line_number: index + 1,
+            line: self.line(index).to_string(),
+        })
+    }
+
+    pub fn handle_key(&mut self, key: Key) -> Option<Action> {
+        match key {
+            Key::Char(c) => {
+                self.query.push(c);
+                self.refresh();
+            }
+            Key::Backspace => {
+                if self.query.pop().is_some() {
+                    self.refresh();
+                }
+            }
+            Key::ToggleCase => {
+                self.ignore_case = !self.ignore_case;
+                self.refresh();
+            }
+            Key::Up => self.select(self.selected.saturating_sub(1)),
+            Key::Down => self.select(self.selected + 1),
+            Key::PageUp => self.select(self.selected.saturating_sub(PAGE)),
+            Key::PageDown => self.select(self.selected + PAGE),
+            Key::Home => self.select(0),
+            Key::End => self.select(usize::MAX),
+            Key::Enter => return self.selected_hit().map(Action::Print),
+            Key::Open => return self.selected_hit().map(Action::Open),
+            Key::Esc => return Some(Action::Quit),
+        }
+
+        None
+    }
+
+    /// Up to `rows` lines of the file centred on the selected hit, as
+    /// `(line_number, line)` pairs.
+    pub fn preview(&self, rows: usize) -> Vec<(usize, &str)> {
+        let Some(&center) = self.hits.get(self.selected) else {
+            return Vec::new();
+        };
+
+        let total = self.line_starts.len();
+        let start = center
+            .saturating_sub(rows / 2)
+            .min(total.saturating_sub(rows));
+        let end = (start + rows).min(total);
+
+        (start..end)
+            .map(|index| (index + 1, self.line(index)))
+            .collect()
+    }
+
+    /// Draws the query box, the result list and the preview pane into a
+    /// `width` x `height` screen.
+    pub fn render(&self, out: &mut impl Write, width: usize, height: usize) -> io::Result<()> {
+        let body = height.saturating_sub(3);
+        let list_rows = body / 2;
+        let preview_rows = body - list_rows;
+
+        write!(out, "\x1B[2J\x1B[H")?;
+
+        let case = if self.ignore_case {
+            "ignore case"
+        } else {
+            "match case"
+        };
+        let status = format!("{} matches ({case}, Tab to toggle)", self.hits.len());
+        write_row(out, &status, width, false)?;
+
+        let first = (self.selected + 1).saturating_sub(list_rows);
+        for row in 0..list_rows {
+            let position = first + row;
+            match self.hits.get(position) {
+                Some(&index) => {
+                    let text = format!("{:>4}: {}", index + 1, self.line(index));
+                    write_row(out, &text, width, position == self.selected)?;
+                }
+                None => write_row(out, "", width, false)?,
+            }
+        }
+
+        write_row(out, &"-".repeat(width), width, false)?;
+
+        let selected_line = self.hits.get(self.selected).map(|index| index + 1);
+        let preview = self.preview(preview_rows);
+        for row in 0..preview_rows {
+            match preview.get(row) {
+                Some(&(number, line)) => {
+                    let marker = if Some(number) == selected_line {
+                        '>'
+                    } else {
+                        ' '
+                    };
+                    let text = format!("{marker}{number:>4}  {line}");
+                    write_row(out, &text, width, false)?;
+                }
+                None => write_row(out, "", width, false)?,
+            }
+        }
+
+        write!(out, "> {}", truncate(&self.query, width.saturating_sub(2)))?;
+        out.flush()
+    }
+
+    fn refresh(&mut self) {
+        let folded = fold_case(&self.query);
+        let matches = |line: &str| {
+            if self.ignore_case {
+                fold_case(line).contains(&folded)
+            } else {
+                line.contains(&self.query)
+            }
+        };
+
+        self.hits = self
+            .contents
+            .lines()
+            .enumerate()
+            .filter(|&(_, line)| matches(line))
+            .map(|(index, _)| index)
+            .collect();
+        self.selected = self.selected.min(self.hits.len().saturating_sub(1));
+    }
+
+    fn select(&mut self, position: usize) {
+        self.selected = position.min(self.hits.len().saturating_sub(1));
+    }
+
+    fn line(&self, index: usize) -> &str {
+        let start = self.line_starts[index];
+        self.contents[start..].lines().next().unwrap_or("")
+    }
+}
+
+fn truncate(text: &str, width: usize) -> &str {
+    match text.char_indices().nth(width) {
+        Some((end, _)) => &text[..end],
+        None => text,
+    }
+}
+
+fn write_row(out: &mut impl Write, text: &str, width: usize, highlight: bool) -> io::Result<()> {
+    let text = truncate(text, width);
+    if highlight {
+        write!(out, "\x1B[7m{text}\x1B[0m\r\n")
+    } else {
+        write!(out, "{text}\r\n")
+    }
+}
+
+/// Restores the terminal even if the browser loop bails out with an error.
+struct RawScreen;
+
+impl RawScreen {
+    fn enter() -> io::Result<RawScreen> {
+        terminal::enable_raw_mode()?;
+        execute!(io::stdout(), terminal::EnterAlternateScreen)?;
+        Ok(RawScreen)
+    }
+}
+
+impl Drop for RawScreen {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+pub fn run(config: Config) -> Result<(), Box<dyn Error>> {
+    let file_path = &config.file_paths[0];
+    let contents = fs::read_to_string(file_path)?;
+    let mut browser = Browser::new(contents, config.query, config.ignore_case);
+
+    let action = {
+        let _screen = RawScreen::enter()?;
+        let mut stdout = io::stdout();
+
+        loop {
+            let (width, height) = terminal::size()?;
+            browser.render(&mut stdout, width as usize, height as usize)?;
+
+            if let Event::Key(event) = event::read()? {
+                if let Some(action) = Key::from_event(event).and_then(|key| browser.handle_key(key))
+                {
+                    break action;
+                }
+            }
+        }
+    };
+
+    match action {
+        Action::Print(hit) => {
+            println!("{}:{}:{}", file_path, hit.line_number, hit.line)
+        }
+        Action::Open(hit) => {
+            let editor = env::var("EDITOR").unwrap_or_else(|_| String::from("vi"));
+            Command::new(editor)
+                .arg(format!("+{}", hit.line_number))
+                .arg(file_path)
+                .status()?;
+        }
+        Action::Quit => {}
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn browser(query: &str) -> Browser {
+        let contents = "\
+Rust:
+safe, fast, productive.
+Pick three.
+Trust me.";
+        Browser::new(contents.to_string(), query.to_string(), false)
+    }
+
+    fn type_str(browser: &mut Browser, text: &str) {
+        for c in text.chars() {
+            assert_eq!(None, browser.handle_key(Key::Char(c)));
+        }
+    }
+
+    #[test]
+    fn search_reruns_as_you_type() {
+        let mut browser = browser("");
+        assert_eq!(4, browser.hits().len());
+
+        type_str(&mut browser, "ust");
+        assert_eq!(vec!["Rust:", "Trust me."], browser.hits());
+
+        for _ in 0..3 {
+            browser.handle_key(Key::Backspace);
+        }
+        type_str(&mut browser, "fa");
+        assert_eq!(vec!["safe, fast, productive."], browser.hits());
+    }
+
+    #[test]
+    fn toggle_case() {
+        let mut browser = browser("rUsT");
+        assert!(browser.hits().is_empty());
+
+        browser.handle_key(Key::ToggleCase);
+        assert!(browser.ignore_case());
+        assert_eq!(vec!["Rust:", "Trust me."], browser.hits());
+    }
+
+    #[test]
+    fn selection_is_clamped() {
+        let mut browser = browser("");
+        browser.handle_key(Key::Up);
+        assert_eq!(0, browser.selected());
+
+        browser.handle_key(Key::PageDown);
+        assert_eq!(3, browser.selected());
+
+        type_str(&mut browser, "Rust");
+        assert_eq!(0, browser.selected());
+    }
+
+    #[test]
+    fn enter_prints_selection() {
+        let mut browser = browser("ust");
+        browser.handle_key(Key::Down);
+
+        let expected = Hit {
+            line_number: 4,
+            line: String::from("Trust me."),
+        };
+        assert_eq!(
+            Some(Action::Print(expected)),
+            browser.handle_key(Key::Enter)
+        );
+        assert_eq!(Some(Action::Quit), browser.handle_key(Key::Esc));
+    }
+
+    #[test]
+    fn enter_without_hits_does_nothing() {
+        let mut browser = browser("duct tape");
+        assert_eq!(None, browser.handle_key(Key::Enter));
+    }
+
+    #[test]
+    fn preview_shows_context() {
+        let mut browser = browser("Pick");
+        assert_eq!(
+            vec![
+                (2, "safe, fast, productive."),
+                (3, "Pick three."),
+                (4, "Trust me.")
+            ],
+            browser.preview(3)
+        );
+
+        for _ in 0..4 {
+            browser.handle_key(Key::Backspace);
+        }
+        assert_eq!(
+            vec![(1, "Rust:"), (2, "safe, fast, productive.")],
+            browser.preview(2)
+        );
+    }
+
+    #[test]
+    fn render_draws_all_panes() {
+        let browser = browser("ust");
+        let mut screen = Vec::new();
+        browser.render(&mut screen, 40, 9).unwrap();
+        let screen = String::from_utf8(screen).unwrap();
+
+        assert!(screen.contains("2 matches"));
+        assert!(screen.contains("\x1B[7m   1: Rust:\x1B[0m"));
+        assert!(screen.contains("   4: Trust me."));
+        assert!(screen.contains(">   1  Rust:"));
+        assert!(screen.ends_with("> ust"));
+    }
+}
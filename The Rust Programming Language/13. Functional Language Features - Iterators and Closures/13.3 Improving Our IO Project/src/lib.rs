@@ -1,53 +1,201 @@
+use std::collections::HashSet;
 use std::env;
 use std::error::Error;
 use std::fs;
+use std::io::{self, Write};
+use std::num::NonZeroUsize;
+use std::thread;
+use std::time::Instant;
+
+pub mod interactive;
+pub mod sort;
+pub mod stats;
+
+use sort::{Sort, SortKey};
+use stats::{FileStats, Stats, Summary};
 
 pub struct Config {
     pub query: String,
-    pub file_path: String,
+    pub file_paths: Vec<String>,
     pub ignore_case: bool,
+    pub interactive: bool,
+    pub stats: Stats,
+    pub sort: Option<Sort>,
+    pub dedup: bool,
 }
 
 impl Config {
-    pub fn build(
-        mut args: impl Iterator<Item = String>,
-    ) -> Result<Config, &'static str> {
+    pub fn build(mut args: impl Iterator<Item = String>) -> Result<Config, &'static str> {
         args.next();
 
-        let query = match args.next() {
+        let mut interactive = false;
+        let mut stats = Stats::Off;
+        let mut sort = None;
+        let mut dedup = false;
+        let mut positional = Vec::new();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--interactive" => interactive = true,
+                "--dedup" => dedup = true,
+                flag if flag.starts_with("--stats") => {
+                    stats = match Stats::parse(flag) {
+                        Some(stats) => stats,
+                        None => return Err("Unknown --stats mode"),
+                    };
+                }
+                flag if flag.starts_with("--sort") => {
+                    let (flag, key) = match flag.split_once('=') {
+                        Some((flag, key)) => (flag, Some(key.to_string())),
+                        None => (flag, args.next()),
+                    };
+                    let reverse = match flag {
+                        "--sort" => false,
+                        "--sortr" => true,
+                        _ => return Err("Unknown option"),
+                    };
+                    let key = match key.as_deref().and_then(SortKey::parse) {
+                        Some(key) => key,
+                        None => return Err("Sort by path, modified, accessed or created"),
+                    };
+                    sort = Some(Sort { key, reverse });
+                }
+                _ => positional.push(arg),
+            }
+        }
+
+        // In interactive mode the query is typed into the browser, so a
+        // lone positional argument is taken to be the file path.
+        if interactive && positional.len() == 1 {
+            positional.insert(0, String::new());
+        }
+
+        let mut positional = positional.into_iter();
+
+        let query = match positional.next() {
             Some(arg) => arg,
             None => return Err("Didn't get a query string"),
         };
 
-        let file_path = match args.next() {
-            Some(arg) => arg,
-            None => return Err("Didn't get a file path"),
-        };
+        let file_paths: Vec<String> = positional.collect();
+        if file_paths.is_empty() {
+            return Err("Didn't get a file path");
+        }
+        if interactive && file_paths.len() > 1 {
+            return Err("Interactive mode takes a single file");
+        }
 
         let ignore_case = env::var("IGNORE_CASE").is_ok();
 
         Ok(Config {
             query,
-            file_path,
+            file_paths,
             ignore_case,
+            interactive,
+            stats,
+            sort,
+            dedup,
         })
     }
 }
 
-pub fn run(config: Config) -> Result<(), Box<dyn Error>> {
-    let contents = fs::read_to_string(config.file_path)?;
+pub fn run(config: Config) -> Result<Summary, Box<dyn Error>> {
+    run_to(config, &mut io::stdout().lock())
+}
 
-    let results = if config.ignore_case {
-        search_case_insensitive(&config.query, &contents)
-    } else {
-        search(&config.query, &contents)
-    };
+/// Like `run`, but writes the matching lines to `out`. Files are read and
+/// searched on worker threads, a batch at a time, and their lines are
+/// written in the order the files were given, or in `--sort` order. A file
+/// that can't be read is reported on stderr, skipped and counted in the
+/// summary's `unreadable`.
+pub fn run_to(config: Config, out: &mut impl Write) -> Result<Summary, Box<dyn Error>> {
+    let start = Instant::now();
+    let mut file_paths = config.file_paths;
 
-    for line in results {
-        println!("{line}");
+    if let Some(sort) = config.sort {
+        sort::sort_paths(&mut file_paths, sort);
     }
 
-    Ok(())
+    let with_path = file_paths.len() > 1;
+    let workers = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    let query = &config.query;
+    let ignore_case = config.ignore_case;
+    let mut seen = HashSet::new();
+    let mut files = Vec::new();
+    let mut unreadable = 0;
+
+    for batch in file_paths.chunks(workers) {
+        let searched: Vec<_> = thread::scope(|scope| {
+            let handles: Vec<_> = batch
+                .iter()
+                .map(|path| scope.spawn(move || search_file(path, query, ignore_case)))
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("searching a file panicked"))
+                .collect()
+        });
+
+        for (path, searched) in batch.iter().zip(searched) {
+            let searched = match searched {
+                Ok(searched) => searched,
+                Err(e) => {
+                    eprintln!("{path}: {e}");
+                    unreadable += 1;
+                    continue;
+                }
+            };
+
+            let mut matches = 0;
+            for line in searched.results {
+                if config.dedup && !seen.insert(line.clone()) {
+                    continue;
+                }
+                matches += 1;
+
+                if with_path {
+                    writeln!(out, "{path}:{line}")?;
+                } else {
+                    writeln!(out, "{line}")?;
+                }
+            }
+
+            files.push(FileStats {
+                path: path.clone(),
+                lines: searched.lines,
+                matches,
+                bytes: searched.bytes,
+            });
+        }
+    }
+
+    Ok(Summary {
+        files,
+        unreadable,
+        elapsed: start.elapsed(),
+    })
+}
+
+/// A file that a worker thread has read and searched.
+struct Searched {
+    lines: usize,
+    bytes: usize,
+    results: Vec<String>,
+}
+
+fn search_file(path: &str, query: &str, ignore_case: bool) -> io::Result<Searched> {
+    let contents = fs::read_to_string(path)?;
+    let results = if ignore_case {
+        search_case_insensitive(query, &contents)
+    } else {
+        search(query, &contents)
+    };
+
+    Ok(Searched {
+        lines: contents.lines().count(),
+        bytes: contents.len(),
+        results: results.into_iter().map(String::from).collect(),
+    })
 }
 
 pub fn search<'a>(query: &str, contents: &'a str) -> Vec<&'a str> {
@@ -57,15 +205,12 @@ pub fn search<'a>(query: &str, contents: &'a str) -> Vec<&'a str> {
         .collect()
 }
 
-pub fn search_case_insensitive<'a>(
-    query: &str,
-    contents: &'a str,
-) -> Vec<&'a str> {
-    let query = query.to_lowercase();
+pub fn search_case_insensitive<'a>(query: &str, contents: &'a str) -> Vec<&'a str> {
+    let query = fold_case(query);
     let mut results = Vec::new();
 
     for line in contents.lines() {
-        if line.to_lowercase().contains(&query) {
+        if fold_case(line).contains(&query) {
             results.push(line);
         }
     }
@@ -73,6 +218,13 @@ pub fn search_case_insensitive<'a>(
     results
 }
 
+// `str::to_lowercase` turns a word-final 'Σ' into 'ς', so a line containing
+// the query could still miss it. Lowercasing one char at a time keeps every
+// substring of the line a substring of the folded line.
+fn fold_case(text: &str) -> String {
+    text.chars().flat_map(char::to_lowercase).collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -103,4 +255,144 @@ Trust me.";
             search_case_insensitive(query, contents)
         );
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn case_insensitive_final_sigma() {
+        let query = "Σ";
+        let contents = "ΟΔΥΣΣΕΥΣ";
+
+        assert_eq!(vec!["ΟΔΥΣΣΕΥΣ"], search_case_insensitive(query, contents));
+    }
+
+    #[test]
+    fn interactive_file_only() {
+        let args = ["minigrep", "--interactive", "poem.txt"].map(String::from);
+        let config = Config::build(args.into_iter()).unwrap();
+
+        assert!(config.interactive);
+        assert_eq!("", config.query);
+        assert_eq!(vec!["poem.txt"], config.file_paths);
+    }
+
+    #[test]
+    fn interactive_with_query() {
+        let args = ["minigrep", "body", "poem.txt", "--interactive"];
+        let config = Config::build(args.map(String::from).into_iter()).unwrap();
+
+        assert!(config.interactive);
+        assert_eq!("body", config.query);
+        assert_eq!(vec!["poem.txt"], config.file_paths);
+    }
+
+    #[test]
+    fn stats_flag() {
+        let args = ["minigrep", "--stats=verbose", "to", "poem.txt"];
+        let config = Config::build(args.map(String::from).into_iter()).unwrap();
+        assert_eq!(Stats::Verbose, config.stats);
+
+        let args = ["minigrep", "to", "poem.txt", "--stats=everything"];
+        let result = Config::build(args.map(String::from).into_iter());
+        assert_eq!(Some("Unknown --stats mode"), result.err());
+    }
+
+    #[test]
+    fn run_returns_summary() {
+        let args = ["minigrep", "nobody", "poem.txt"];
+        let config = Config::build(args.map(String::from).into_iter()).unwrap();
+        let summary = run(config).unwrap();
+
+        assert_eq!(1, summary.files_searched());
+        assert_eq!(1, summary.files_matched());
+        assert_eq!(9, summary.lines_scanned());
+        assert_eq!(2, summary.matches());
+        assert_eq!(
+            fs::metadata("poem.txt").unwrap().len() as usize,
+            summary.bytes_read()
+        );
+    }
+
+    #[test]
+    fn sort_and_dedup_flags() {
+        let args = ["minigrep", "--sortr", "modified", "to", "a.txt", "b.txt"];
+        let config = Config::build(args.map(String::from).into_iter()).unwrap();
+        let sort = Sort {
+            key: SortKey::Modified,
+            reverse: true,
+        };
+        assert_eq!(Some(sort), config.sort);
+        assert_eq!(vec!["a.txt", "b.txt"], config.file_paths);
+        assert!(!config.dedup);
+
+        let args = ["minigrep", "--sort=path", "--dedup", "to", "a.txt"];
+        let config = Config::build(args.map(String::from).into_iter()).unwrap();
+        assert_eq!(SortKey::Path, config.sort.unwrap().key);
+        assert!(config.dedup);
+
+        let args = ["minigrep", "to", "a.txt", "--sort", "size"];
+        let result = Config::build(args.map(String::from).into_iter());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn dedup_across_files() {
+        let args = ["minigrep", "--dedup", "nobody", "poem.txt", "poem.txt"];
+        let config = Config::build(args.map(String::from).into_iter()).unwrap();
+
+        let mut out = Vec::new();
+        let summary = run_to(config, &mut out).unwrap();
+
+        assert_eq!(
+            "\
+poem.txt:I'm nobody! Who are you?
+poem.txt:Are you nobody, too?
+",
+            String::from_utf8(out).unwrap()
+        );
+        assert_eq!(2, summary.matches());
+        assert_eq!(0, summary.files[1].matches);
+    }
+
+    #[test]
+    fn results_follow_the_file_order() {
+        let dir = env::temp_dir().join(format!("minigrep-order-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let mut args = vec![String::from("minigrep"), String::from("line")];
+        for i in 0..12 {
+            let path = dir.join(format!("{i}.txt"));
+            fs::write(&path, format!("line {i}\nother\n")).unwrap();
+            args.push(path.to_string_lossy().into_owned());
+        }
+        let config = Config::build(args.clone().into_iter()).unwrap();
+
+        let mut out = Vec::new();
+        let summary = run_to(config, &mut out).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let expected: String = args[2..]
+            .iter()
+            .enumerate()
+            .map(|(i, path)| format!("{path}:line {i}\n"))
+            .collect();
+        assert_eq!(expected, String::from_utf8(out).unwrap());
+        assert_eq!(12, summary.files_searched());
+    }
+
+    #[test]
+    fn unreadable_file_is_skipped() {
+        let args = ["minigrep", "nobody", "missing.txt", "poem.txt"];
+        let config = Config::build(args.map(String::from).into_iter()).unwrap();
+
+        let mut out = Vec::new();
+        let summary = run_to(config, &mut out).unwrap();
+
+        assert_eq!(1, summary.unreadable);
+        assert_eq!(1, summary.files_searched());
+        assert_eq!(
+            "\
+poem.txt:I'm nobody! Who are you?
+poem.txt:Are you nobody, too?
+",
+            String::from_utf8(out).unwrap()
+        );
+    }
+}
@@ -1,7 +1,8 @@
 use std::env;
 use std::process;
 
-use improving_our_io_project::Config;
+use improving_our_io_project::stats::Stats;
+use improving_our_io_project::{interactive, Config};
 
 fn main() {
     let config = Config::build(env::args()).unwrap_or_else(|err| {
@@ -9,8 +10,32 @@ fn main() {
         process::exit(1);
     });
 
-    if let Err(e) = improving_our_io_project::run(config) {
-        eprintln!("Application error: {e}");
-        process::exit(1);
+    if config.interactive {
+        if let Err(e) = interactive::run(config) {
+            eprintln!("Application error: {e}");
+            process::exit(1);
+        }
+        return;
+    }
+
+    let stats = config.stats;
+
+    match improving_our_io_project::run(config) {
+        Ok(summary) => {
+            if stats != Stats::Off {
+                eprint!("{}", summary.report(stats == Stats::Verbose));
+            }
+            if summary.unreadable > 0 {
+                eprintln!(
+                    "Application error: couldn't read {} of the files",
+                    summary.unreadable
+                );
+                process::exit(1);
+            }
+        }
+        Err(e) => {
+            eprintln!("Application error: {e}");
+            process::exit(1);
+        }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,115 @@
+use std::fs::{self, Metadata};
+use std::io;
+use std::time::SystemTime;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortKey {
+    Path,
+    Modified,
+    Accessed,
+    Created,
+}
+
+impl SortKey {
+    pub fn parse(name: &str) -> Option<SortKey> {
+        match name {
+            "path" => Some(SortKey::Path),
+            "modified" => Some(SortKey::Modified),
+            "accessed" => Some(SortKey::Accessed),
+            "created" => Some(SortKey::Created),
+            _ => None,
+        }
+    }
+
+    fn time(self, metadata: io::Result<Metadata>) -> Option<SystemTime> {
+        let metadata = metadata.ok()?;
+        match self {
+            SortKey::Path => None,
+            SortKey::Modified => metadata.modified().ok(),
+            SortKey::Accessed => metadata.accessed().ok(),
+            SortKey::Created => metadata.created().ok(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sort {
+    pub key: SortKey,
+    pub reverse: bool,
+}
+
+/// Orders the files to search. Files whose timestamp can't be read (missing
+/// files, or `created` on platforms without it) sort first, by path.
+pub fn sort_paths(paths: &mut [String], sort: Sort) {
+    match sort.key {
+        SortKey::Path => paths.sort(),
+        key => paths.sort_by_cached_key(|path| (key.time(fs::metadata(path)), path.clone())),
+    }
+
+    if sort.reverse {
+        paths.reverse();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::ffi::OsStr;
+    use std::fs::File;
+    use std::path::Path;
+    use std::time::Duration;
+
+    #[test]
+    fn parse() {
+        assert_eq!(Some(SortKey::Path), SortKey::parse("path"));
+        assert_eq!(Some(SortKey::Created), SortKey::parse("created"));
+        assert_eq!(None, SortKey::parse("size"));
+    }
+
+    #[test]
+    fn by_path() {
+        let mut paths = ["b.txt", "c.txt", "a.txt"].map(String::from);
+        let sort = Sort {
+            key: SortKey::Path,
+            reverse: false,
+        };
+        sort_paths(&mut paths, sort);
+        assert_eq!(["a.txt", "b.txt", "c.txt"], paths);
+
+        let reversed = Sort {
+            reverse: true,
+            ..sort
+        };
+        sort_paths(&mut paths, reversed);
+        assert_eq!(["c.txt", "b.txt", "a.txt"], paths);
+    }
+
+    #[test]
+    fn by_modified() {
+        let dir = env::temp_dir().join(format!("sort-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let epoch = SystemTime::UNIX_EPOCH;
+        let mut paths = Vec::new();
+        for (name, age) in [("old", 10), ("new", 30), ("middle", 20)] {
+            let path = dir.join(name);
+            let file = File::create(&path).unwrap();
+            file.set_modified(epoch + Duration::from_secs(age)).unwrap();
+            paths.push(path.to_string_lossy().into_owned());
+        }
+
+        let sort = Sort {
+            key: SortKey::Modified,
+            reverse: true,
+        };
+        sort_paths(&mut paths, sort);
+        fs::remove_dir_all(&dir).unwrap();
+
+        let names: Vec<&OsStr> = paths
+            .iter()
+            .map(|path| Path::new(path).file_name().unwrap())
+            .collect();
+        assert_eq!(["new", "middle", "old"].map(OsStr::new), names.as_slice());
+    }
+}
@@ -0,0 +1,157 @@
+use std::fmt::Write;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stats {
+    Off,
+    Summary,
+    Verbose,
+}
+
+impl Stats {
+    pub fn parse(arg: &str) -> Option<Stats> {
+        match arg {
+            "--stats" => Some(Stats::Summary),
+            "--stats=verbose" => Some(Stats::Verbose),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileStats {
+    pub path: String,
+    pub lines: usize,
+    /// Matching lines that were printed. With `--dedup`, lines already
+    /// printed for an earlier file aren't counted again.
+    pub matches: usize,
+    pub bytes: usize,
+}
+
+/// What a call to `run` did: one entry per file searched, how many files
+/// couldn't be read, and the wall-clock time for the whole run.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Summary {
+    pub files: Vec<FileStats>,
+    pub unreadable: usize,
+    pub elapsed: Duration,
+}
+
+impl Summary {
+    pub fn files_searched(&self) -> usize {
+        self.files.len()
+    }
+
+    pub fn files_matched(&self) -> usize {
+        self.files.iter().filter(|file| file.matches > 0).count()
+    }
+
+    pub fn lines_scanned(&self) -> usize {
+        self.files.iter().map(|file| file.lines).sum()
+    }
+
+    pub fn matches(&self) -> usize {
+        self.files.iter().map(|file| file.matches).sum()
+    }
+
+    pub fn bytes_read(&self) -> usize {
+        self.files.iter().map(|file| file.bytes).sum()
+    }
+
+    pub fn report(&self, verbose: bool) -> String {
+        let mut report = String::new();
+
+        if verbose {
+            for file in &self.files {
+                let _ = writeln!(
+                    report,
+                    "{}: {} matches in {} lines ({} bytes)",
+                    file.path, file.matches, file.lines, file.bytes
+                );
+            }
+        }
+
+        let _ = writeln!(report, "files searched: {}", self.files_searched());
+        if self.unreadable > 0 {
+            let _ = writeln!(report, "files unreadable: {}", self.unreadable);
+        }
+        let _ = writeln!(report, "files matched: {}", self.files_matched());
+        let _ = writeln!(report, "lines scanned: {}", self.lines_scanned());
+        let _ = writeln!(report, "matches: {}", self.matches());
+        let _ = writeln!(report, "bytes read: {}", self.bytes_read());
+        let _ = writeln!(report, "elapsed: {:?}", self.elapsed);
+
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary() -> Summary {
+        Summary {
+            files: vec![
+                FileStats {
+                    path: String::from("poem.txt"),
+                    lines: 9,
+                    matches: 2,
+                    bytes: 220,
+                },
+                FileStats {
+                    path: String::from("empty.txt"),
+                    lines: 0,
+                    matches: 0,
+                    bytes: 0,
+                },
+            ],
+            unreadable: 0,
+            elapsed: Duration::from_millis(3),
+        }
+    }
+
+    #[test]
+    fn totals() {
+        let summary = summary();
+
+        assert_eq!(2, summary.files_searched());
+        assert_eq!(1, summary.files_matched());
+        assert_eq!(9, summary.lines_scanned());
+        assert_eq!(2, summary.matches());
+        assert_eq!(220, summary.bytes_read());
+    }
+
+    #[test]
+    fn report() {
+        let summary = summary();
+
+        assert_eq!(
+            "\
+files searched: 2
+files matched: 1
+lines scanned: 9
+matches: 2
+bytes read: 220
+elapsed: 3ms
+",
+            summary.report(false)
+        );
+        let with_unreadable = Summary {
+            unreadable: 1,
+            ..summary.clone()
+        };
+        assert!(with_unreadable
+            .report(false)
+            .starts_with("files searched: 2\nfiles unreadable: 1\n"));
+        assert!(summary
+            .report(true)
+            .starts_with("poem.txt: 2 matches in 9 lines (220 bytes)\n"));
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(Some(Stats::Summary), Stats::parse("--stats"));
+        assert_eq!(Some(Stats::Verbose), Stats::parse("--stats=verbose"));
+        assert_eq!(None, Stats::parse("--stats=loud"));
+    }
+}
@@ -0,0 +1,105 @@
+//! End-to-end runs of the binary, one per directory under `tests/golden`.
+//!
+//! Each directory describes a run: `args` has one argument per line and an
+//! optional `env` has `KEY=VALUE` lines. The expected results sit next to
+//! them in `stdout`, `stderr` and `status`. Runs start in the crate root,
+//! where `poem.txt` is, and `tests/fixtures` holds the other files searched.
+//!
+//! Two things differ from machine to machine and are replaced before the
+//! comparison: the time in the `--stats` report, and the wording of OS
+//! errors, of which only the `(os error N)` code is kept.
+//!
+//! Set `BLESS=1` to write what the binary printed as the new expectation.
+
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// Replaces what depends on the machine rather than on minigrep.
+fn stable(output: &[u8]) -> String {
+    String::from_utf8_lossy(output)
+        .split_inclusive('\n')
+        .map(|line| {
+            if line.starts_with("elapsed: ") {
+                return String::from("elapsed: <time>\n");
+            }
+            let Some(code) = line.find("(os error ") else {
+                return line.to_string();
+            };
+            match line[..code].rfind(": ") {
+                Some(start) => format!("{}{}", &line[..start + 2], &line[code..]),
+                None => line.to_string(),
+            }
+        })
+        .collect()
+}
+
+/// The lines of `path`, or none if there is no such file.
+fn lines(path: &Path) -> Vec<String> {
+    fs::read_to_string(path)
+        .map(|text| text.lines().map(String::from).collect())
+        .unwrap_or_default()
+}
+
+/// Runs the case in `dir` and describes every way its results differ from
+/// what was expected.
+fn check(root: &Path, dir: &Path, bless: bool) -> Vec<String> {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_improving_our_io_project"));
+    command
+        .args(lines(&dir.join("args")))
+        .current_dir(root)
+        .env_remove("IGNORE_CASE");
+    for pair in lines(&dir.join("env")) {
+        if let Some((key, value)) = pair.split_once('=') {
+            command.env(key, value);
+        }
+    }
+    let output = command.output().expect("the binary should run");
+
+    let status = output
+        .status
+        .code()
+        .map_or_else(|| String::from("signal\n"), |code| format!("{code}\n"));
+    let results = [
+        ("stdout", stable(&output.stdout)),
+        ("stderr", stable(&output.stderr)),
+        ("status", status),
+    ];
+
+    let case = dir.file_name().unwrap().to_string_lossy();
+    let mut differences = Vec::new();
+    for (name, actual) in results {
+        let path = dir.join(name);
+        if bless {
+            fs::write(&path, &actual).unwrap();
+        } else if let Ok(expected) = fs::read_to_string(&path) {
+            if expected != actual {
+                differences.push(format!(
+                    "{case}/{name}:\n--- expected\n{expected}--- actual\n{actual}"
+                ));
+            }
+        } else {
+            differences.push(format!("{case}/{name} is missing, bless to create it"));
+        }
+    }
+    differences
+}
+
+#[test]
+fn golden() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let bless = env::var_os("BLESS").is_some();
+
+    let mut dirs: Vec<_> = fs::read_dir(root.join("tests/golden"))
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    dirs.sort();
+
+    let differences: Vec<String> = dirs
+        .iter()
+        .flat_map(|dir| check(root, dir, bless))
+        .collect();
+    assert!(differences.is_empty(), "\n{}", differences.join("\n"));
+}
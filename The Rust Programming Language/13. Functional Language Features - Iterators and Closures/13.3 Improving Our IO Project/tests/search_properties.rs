@@ -0,0 +1,114 @@
+use improving_our_io_project::{search, search_case_insensitive};
+use proptest::prelude::*;
+
+/// Lines of arbitrary printable Unicode, mixed with a few characters whose
+/// case mappings are awkward.
+fn lines() -> impl Strategy<Value = Vec<String>> {
+    let line = prop_oneof!["\\PC{0,12}", "[aAsSσΣςİıßẞ ]{0,12}",];
+    prop::collection::vec(line, 0..8)
+}
+
+/// Either a piece of one of the lines, so there is something to find, or an
+/// unrelated string.
+fn contents_and_query() -> impl Strategy<Value = (String, String)> {
+    lines().prop_flat_map(|lines| {
+        let contents = lines.join("\n");
+        let pieces: Vec<String> = lines
+            .iter()
+            .flat_map(|line| {
+                let chars: Vec<char> = line.chars().collect();
+                (0..chars.len()).flat_map(move |start| {
+                    let chars = chars.clone();
+                    (start + 1..=chars.len().min(start + 3))
+                        .map(move |end| chars[start..end].iter().collect())
+                })
+            })
+            .collect();
+
+        let query = if pieces.is_empty() {
+            "\\PC{0,3}".boxed()
+        } else {
+            prop_oneof![prop::sample::select(pieces), "\\PC{0,3}"].boxed()
+        };
+
+        (Just(contents), query)
+    })
+}
+
+/// Whether `results` appear in `contents` in the same order as in the file.
+fn in_file_order(results: &[&str], contents: &str) -> bool {
+    let mut lines = contents.lines().map(str::as_ptr);
+    results
+        .iter()
+        .all(|result| lines.any(|line| line == result.as_ptr()))
+}
+
+/// Whether `query` appears in `line`, found by comparing `query` with every
+/// run of as many chars in `line` rather than with `str::contains`.
+fn has_window(line: &str, query: &str) -> bool {
+    let line: Vec<char> = line.chars().collect();
+    let query: Vec<char> = query.chars().collect();
+    query.is_empty() || line.windows(query.len()).any(|window| window == query)
+}
+
+proptest! {
+    #[test]
+    fn every_result_contains_the_query((contents, query) in contents_and_query()) {
+        for line in search(&query, &contents) {
+            prop_assert!(line.contains(&query));
+        }
+    }
+
+    #[test]
+    fn no_matching_line_is_omitted((contents, query) in contents_and_query()) {
+        let results: Vec<*const u8> = search(&query, &contents)
+            .iter()
+            .map(|line| line.as_ptr())
+            .collect();
+
+        for line in contents.lines().filter(|line| has_window(line, &query)) {
+            prop_assert!(results.contains(&line.as_ptr()), "{line:?} missing");
+        }
+    }
+
+    #[test]
+    fn case_insensitive_matches_lowercased_ascii(
+        lines in prop::collection::vec("[a-zA-Z ]{0,12}", 0..8),
+        query in "[a-zA-Z]{0,3}",
+    ) {
+        let contents = lines.join("\n");
+        let query_lower = query.to_lowercase();
+        let expected: Vec<&str> = contents
+            .lines()
+            .filter(|line| line.to_lowercase().contains(&query_lower))
+            .collect();
+
+        prop_assert_eq!(expected, search_case_insensitive(&query, &contents));
+    }
+
+    #[test]
+    fn results_keep_file_order((contents, query) in contents_and_query()) {
+        prop_assert!(in_file_order(&search(&query, &contents), &contents));
+        prop_assert!(in_file_order(&search_case_insensitive(&query, &contents), &contents));
+    }
+
+    #[test]
+    fn case_insensitive_is_a_superset((contents, query) in contents_and_query()) {
+        let insensitive: Vec<*const u8> = search_case_insensitive(&query, &contents)
+            .iter()
+            .map(|line| line.as_ptr())
+            .collect();
+
+        for line in search(&query, &contents) {
+            prop_assert!(insensitive.contains(&line.as_ptr()), "{line:?} missing");
+        }
+    }
+
+    #[test]
+    fn case_insensitive_ignores_ascii_case((contents, query) in contents_and_query()) {
+        let upper = search_case_insensitive(&query.to_ascii_uppercase(), &contents);
+        let lower = search_case_insensitive(&query.to_ascii_lowercase(), &contents);
+
+        prop_assert_eq!(upper, lower);
+    }
+}
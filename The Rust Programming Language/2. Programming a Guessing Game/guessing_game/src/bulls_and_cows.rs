@@ -0,0 +1,146 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::score::Puzzle;
+use crate::variant::{Clue, Variant};
+
+const DIGITS: usize = 4;
+const MAX_ATTEMPTS: u32 = 10;
+
+/// Guess a number made of four different digits. A bull is a right digit in
+/// the right place, a cow a right digit in the wrong place.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BullsAndCows {
+    secret: [u8; DIGITS],
+}
+
+impl BullsAndCows {
+    pub fn new(rng: &mut impl Rng) -> BullsAndCows {
+        let mut digits: Vec<u8> = (0..10).collect();
+        digits.shuffle(rng);
+
+        let mut secret = [0; DIGITS];
+        secret.copy_from_slice(&digits[..DIGITS]);
+        BullsAndCows { secret }
+    }
+
+    /// Counts the bulls and cows a guess scores against the secret.
+    pub fn score(&self, guess: &[u8; DIGITS]) -> (usize, usize) {
+        let bulls = guess
+            .iter()
+            .zip(&self.secret)
+            .filter(|(guessed, secret)| guessed == secret)
+            .count();
+        let shared = guess
+            .iter()
+            .filter(|digit| self.secret.contains(digit))
+            .count();
+        (bulls, shared - bulls)
+    }
+
+    fn parse(guess: &str) -> Result<[u8; DIGITS], String> {
+        let guess = guess.trim();
+        let digits: Vec<u8> = guess
+            .chars()
+            .map(|c| c.to_digit(10).map(|digit| digit as u8))
+            .collect::<Option<_>>()
+            .ok_or_else(|| format!("'{guess}' should only have digits."))?;
+
+        if digits.len() != DIGITS {
+            return Err(format!("Please type {DIGITS} digits."));
+        }
+        let mut parsed = [0; DIGITS];
+        parsed.copy_from_slice(&digits);
+
+        for (i, digit) in parsed.iter().enumerate() {
+            if parsed[..i].contains(digit) {
+                return Err(format!("The digits must all differ, {digit} is repeated."));
+            }
+        }
+        Ok(parsed)
+    }
+}
+
+impl Variant for BullsAndCows {
+    fn rules(&self) -> String {
+        format!(
+            "Guess the {DIGITS} different digits. A bull is a right digit in the right place,\n\
+             a cow is a right digit in the wrong place."
+        )
+    }
+
+    fn puzzle(&self) -> Puzzle {
+        Puzzle {
+            name: "Bulls and cows",
+            // 10 * 9 * 8 * 7 numbers with four different digits.
+            possibilities: 5040,
+            max_attempts: MAX_ATTEMPTS,
+        }
+    }
+
+    fn check(&self, guess: &str) -> Result<Clue, String> {
+        let (bulls, cows) = self.score(&BullsAndCows::parse(guess)?);
+        Ok(Clue {
+            text: format!("{bulls} bulls, {cows} cows"),
+            solved: bulls == DIGITS,
+        })
+    }
+
+    fn secret(&self) -> String {
+        self.secret.iter().map(|digit| digit.to_string()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::make_rng;
+
+    #[test]
+    fn secret_has_different_digits() {
+        let mut rng = make_rng(Some(1));
+        for _ in 0..20 {
+            let game = BullsAndCows::new(&mut rng);
+            let secret = game.secret();
+            assert_eq!(DIGITS, secret.len());
+            assert!(BullsAndCows::parse(&secret).is_ok(), "{secret}");
+        }
+    }
+
+    #[test]
+    fn bulls_and_cows() {
+        let game = BullsAndCows {
+            secret: [1, 2, 3, 4],
+        };
+
+        assert_eq!((4, 0), game.score(&[1, 2, 3, 4]));
+        assert_eq!((0, 4), game.score(&[4, 3, 2, 1]));
+        assert_eq!((1, 2), game.score(&[1, 3, 2, 9]));
+        assert_eq!((0, 0), game.score(&[5, 6, 7, 8]));
+
+        let clue = game.check("1392\n").unwrap();
+        assert_eq!("1 bulls, 2 cows", clue.text);
+        assert!(!clue.solved);
+        assert!(game.check("1234").unwrap().solved);
+    }
+
+    #[test]
+    fn invalid_guesses() {
+        let game = BullsAndCows {
+            secret: [1, 2, 3, 4],
+        };
+
+        assert_eq!(
+            Err(String::from("Please type 4 digits.")),
+            game.check("123")
+        );
+        assert_eq!(
+            Err(String::from("'12a4' should only have digits.")),
+            game.check("12a4")
+        );
+        assert_eq!(
+            Err(String::from("The digits must all differ, 1 is repeated.")),
+            game.check("1231")
+        );
+    }
+}
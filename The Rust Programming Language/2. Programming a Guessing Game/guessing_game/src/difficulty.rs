@@ -0,0 +1,100 @@
+use std::fmt;
+use std::ops::RangeInclusive;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+    Custom { low: u32, high: u32, attempts: u32 },
+}
+
+impl Difficulty {
+    pub fn parse(name: &str) -> Option<Difficulty> {
+        match name {
+            "easy" => Some(Difficulty::Easy),
+            "normal" => Some(Difficulty::Normal),
+            "hard" => Some(Difficulty::Hard),
+            _ => None,
+        }
+    }
+
+    pub fn custom(low: u32, high: u32, attempts: u32) -> Result<Difficulty, &'static str> {
+        if low > high {
+            return Err("The lowest number must not be above the highest");
+        }
+        if attempts == 0 {
+            return Err("At least one attempt is needed");
+        }
+
+        Ok(Difficulty::Custom {
+            low,
+            high,
+            attempts,
+        })
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Normal => "Normal",
+            Difficulty::Hard => "Hard",
+            Difficulty::Custom { .. } => "Custom",
+        }
+    }
+
+    pub fn range(&self) -> RangeInclusive<u32> {
+        match *self {
+            Difficulty::Easy => 1..=10,
+            Difficulty::Normal => 1..=100,
+            Difficulty::Hard => 1..=1000,
+            Difficulty::Custom { low, high, .. } => low..=high,
+        }
+    }
+
+    pub fn max_attempts(&self) -> u32 {
+        match *self {
+            Difficulty::Easy => 5,
+            Difficulty::Normal => 7,
+            Difficulty::Hard => 10,
+            Difficulty::Custom { attempts, .. } => attempts,
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let range = self.range();
+        write!(
+            f,
+            "{} ({}-{}, {} attempts)",
+            self.name(),
+            range.start(),
+            range.end(),
+            self.max_attempts()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presets() {
+        assert_eq!(Some(Difficulty::Hard), Difficulty::parse("hard"));
+        assert_eq!(None, Difficulty::parse("custom"));
+        assert_eq!(1..=100, Difficulty::Normal.range());
+        assert_eq!("Easy (1-10, 5 attempts)", Difficulty::Easy.to_string());
+    }
+
+    #[test]
+    fn custom() {
+        let difficulty = Difficulty::custom(5, 50, 3).unwrap();
+        assert_eq!(5..=50, difficulty.range());
+        assert_eq!(3, difficulty.max_attempts());
+
+        assert!(Difficulty::custom(50, 5, 3).is_err());
+        assert!(Difficulty::custom(1, 5, 0).is_err());
+    }
+}
@@ -0,0 +1,141 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::RangeInclusive;
+
+use crate::difficulty::Difficulty;
+use crate::guess::{Guess, GuessError};
+use crate::solver::Interval;
+use crate::Outcome;
+
+/// Why a guess was not counted as an attempt.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Rejected {
+    Invalid(GuessError),
+    Repeated(u32),
+}
+
+impl fmt::Display for Rejected {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Rejected::Invalid(err) => write!(f, "{err}"),
+            Rejected::Repeated(guess) => {
+                write!(f, "You already guessed {guess}, try another number.")
+            }
+        }
+    }
+}
+
+/// The state of one game, shared by the plain and the TUI front end.
+pub struct Game {
+    secret_number: u32,
+    range: RangeInclusive<u32>,
+    max_attempts: u32,
+    history: Vec<(u32, Ordering)>,
+    interval: Interval,
+}
+
+impl Game {
+    pub fn new(secret_number: u32, difficulty: &Difficulty) -> Game {
+        let range = difficulty.range();
+        Game {
+            secret_number,
+            interval: Interval::new(&range),
+            range,
+            max_attempts: difficulty.max_attempts(),
+            history: Vec::new(),
+        }
+    }
+
+    /// Checks a typed guess against the secret, returning the guess and
+    /// `guess.cmp(&secret)`. Rejected guesses don't use up an attempt.
+    pub fn guess(&mut self, input: &str) -> Result<(u32, Ordering), Rejected> {
+        let guess = Guess::parse(input, &self.range)
+            .map_err(Rejected::Invalid)?
+            .value();
+
+        if self.history.iter().any(|&(previous, _)| previous == guess) {
+            return Err(Rejected::Repeated(guess));
+        }
+
+        let answer = guess.cmp(&self.secret_number);
+        self.history.push((guess, answer));
+        self.interval.narrow(guess, answer);
+        Ok((guess, answer))
+    }
+
+    /// `None` while the game is still going.
+    pub fn outcome(&self) -> Option<Outcome> {
+        let attempts = self.attempts();
+        match self.history.last() {
+            Some((_, Ordering::Equal)) => Some(Outcome::Won { attempts }),
+            _ if attempts == self.max_attempts => Some(Outcome::Lost),
+            _ => None,
+        }
+    }
+
+    pub fn secret_number(&self) -> u32 {
+        self.secret_number
+    }
+
+    pub fn range(&self) -> &RangeInclusive<u32> {
+        &self.range
+    }
+
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    pub fn attempts(&self) -> u32 {
+        self.history.len() as u32
+    }
+
+    pub fn attempts_left(&self) -> u32 {
+        self.max_attempts - self.attempts()
+    }
+
+    /// Every counted guess with its answer, oldest first.
+    pub fn history(&self) -> &[(u32, Ordering)] {
+        &self.history
+    }
+
+    /// The numbers the earlier answers still allow.
+    pub fn interval(&self) -> &Interval {
+        &self.interval
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_only_new_valid_guesses() {
+        let mut game = Game::new(7, &Difficulty::Easy);
+
+        assert_eq!(Ok((3, Ordering::Less)), game.guess("3\n"));
+        assert_eq!(Err(Rejected::Repeated(3)), game.guess("3"));
+        assert_eq!(
+            Err(Rejected::Invalid(GuessError::NotANumber(String::from("x")))),
+            game.guess("x")
+        );
+        assert_eq!(1, game.attempts());
+        assert_eq!(4, game.attempts_left());
+        assert_eq!(None, game.outcome());
+
+        assert_eq!(Ok((9, Ordering::Greater)), game.guess("9"));
+        assert_eq!((4, 8), (game.interval().low(), game.interval().high()));
+        assert_eq!(Ok((7, Ordering::Equal)), game.guess("7"));
+        assert_eq!(Some(Outcome::Won { attempts: 3 }), game.outcome());
+    }
+
+    #[test]
+    fn lost_after_the_last_attempt() {
+        let difficulty = Difficulty::custom(1, 10, 2).unwrap();
+        let mut game = Game::new(7, &difficulty);
+        game.guess("1").unwrap();
+        game.guess("2").unwrap();
+
+        assert_eq!(Some(Outcome::Lost), game.outcome());
+        assert_eq!(&[(1, Ordering::Less), (2, Ordering::Less)], game.history());
+    }
+}
@@ -0,0 +1,104 @@
+use std::fmt;
+use std::ops::RangeInclusive;
+
+/// A guess that is known to be inside the game's range, like the `Guess`
+/// type from chapter 9.3 but reporting bad input instead of panicking.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Guess {
+    value: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GuessError {
+    Empty,
+    NotANumber(String),
+    OutOfRange { value: i64, low: u32, high: u32 },
+}
+
+impl Guess {
+    pub fn new(value: u32, range: &RangeInclusive<u32>) -> Result<Guess, GuessError> {
+        Guess::check(i64::from(value), range)
+    }
+
+    pub fn parse(input: &str, range: &RangeInclusive<u32>) -> Result<Guess, GuessError> {
+        let input = input.trim();
+        if input.is_empty() {
+            return Err(GuessError::Empty);
+        }
+
+        match input.parse() {
+            Ok(value) => Guess::check(value, range),
+            Err(_) => Err(GuessError::NotANumber(input.to_string())),
+        }
+    }
+
+    pub fn value(&self) -> u32 {
+        self.value
+    }
+
+    fn check(value: i64, range: &RangeInclusive<u32>) -> Result<Guess, GuessError> {
+        match u32::try_from(value) {
+            Ok(value) if range.contains(&value) => Ok(Guess { value }),
+            _ => Err(GuessError::OutOfRange {
+                value,
+                low: *range.start(),
+                high: *range.end(),
+            }),
+        }
+    }
+}
+
+impl fmt::Display for GuessError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GuessError::Empty => write!(f, "Please type a number."),
+            GuessError::NotANumber(input) => write!(f, "'{input}' is not a whole number."),
+            GuessError::OutOfRange { value, low, high } => {
+                write!(
+                    f,
+                    "{value} is out of range, guess between {low} and {high}."
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid() {
+        assert_eq!(7, Guess::parse(" 7\n", &(1..=10)).unwrap().value());
+        assert_eq!(Ok(Guess { value: 10 }), Guess::new(10, &(1..=10)));
+    }
+
+    #[test]
+    fn not_a_number() {
+        assert_eq!(Err(GuessError::Empty), Guess::parse("\n", &(1..=10)));
+        assert_eq!(
+            Err(GuessError::NotANumber(String::from("seven"))),
+            Guess::parse("seven", &(1..=10))
+        );
+        assert_eq!(
+            "'4.5' is not a whole number.",
+            Guess::parse("4.5", &(1..=10)).unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn out_of_range() {
+        assert_eq!(
+            Err(GuessError::OutOfRange {
+                value: -3,
+                low: 1,
+                high: 10
+            }),
+            Guess::parse("-3", &(1..=10))
+        );
+        assert_eq!(
+            "11 is out of range, guess between 1 and 10.",
+            Guess::new(11, &(1..=10)).unwrap_err().to_string()
+        );
+    }
+}
@@ -0,0 +1,262 @@
+use std::cmp::Reverse;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+const HEADER: &str = "# guessing_game leaderboard v1";
+const SIZE: usize = 10;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub name: String,
+    pub score: u32,
+    pub difficulty: String,
+    pub attempts: u32,
+    pub seconds: u64,
+}
+
+impl Entry {
+    fn parse(line: &str) -> Option<Entry> {
+        let mut fields = line.split('\t');
+        let entry = Entry {
+            name: fields.next()?.to_string(),
+            score: fields.next()?.parse().ok()?,
+            difficulty: fields.next()?.to_string(),
+            attempts: fields.next()?.parse().ok()?,
+            seconds: fields.next()?.parse().ok()?,
+        };
+
+        if fields.next().is_some() || entry.name.is_empty() {
+            return None;
+        }
+        Some(entry)
+    }
+}
+
+/// Tabs and newlines would break the file format, so they become spaces.
+fn clean(text: &str) -> String {
+    text.trim()
+        .chars()
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .collect()
+}
+
+/// The best scores, highest first.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Leaderboard {
+    entries: Vec<Entry>,
+}
+
+impl Leaderboard {
+    /// Reads the leaderboard at `path`. A missing file is an empty
+    /// leaderboard, and lines that can't be parsed are dropped; how many were
+    /// dropped is returned so the caller can warn about it.
+    pub fn load(path: &Path) -> io::Result<(Leaderboard, usize)> {
+        let bytes = match fs::read(path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                return Ok((Leaderboard::default(), 0))
+            }
+            Err(e) => return Err(e),
+        };
+
+        let mut leaderboard = Leaderboard::default();
+        let mut skipped = 0;
+
+        for line in String::from_utf8_lossy(&bytes).lines() {
+            if line == HEADER || line.trim().is_empty() {
+                continue;
+            }
+            match Entry::parse(line) {
+                Some(entry) => leaderboard.entries.push(entry),
+                None => skipped += 1,
+            }
+        }
+
+        leaderboard.sort();
+        Ok((leaderboard, skipped))
+    }
+
+    /// Writes to a temporary file next to `path` and renames it into place,
+    /// so a crash never leaves a half-written leaderboard behind.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut temporary = PathBuf::from(path);
+        temporary.as_mut_os_string().push(".tmp");
+
+        let mut file = File::create(&temporary)?;
+        writeln!(file, "{HEADER}")?;
+        for entry in &self.entries {
+            writeln!(
+                file,
+                "{}\t{}\t{}\t{}\t{}",
+                entry.name, entry.score, entry.difficulty, entry.attempts, entry.seconds
+            )?;
+        }
+        file.sync_all()?;
+
+        fs::rename(&temporary, path)
+    }
+
+    /// Adds `entry` and returns its 1-based rank, or `None` if it didn't
+    /// make the board.
+    pub fn add(&mut self, mut entry: Entry) -> Option<usize> {
+        entry.name = clean(&entry.name);
+        entry.difficulty = clean(&entry.difficulty);
+        if entry.name.is_empty() {
+            return None;
+        }
+
+        let rank = self
+            .entries
+            .iter()
+            .position(|other| other.score < entry.score)
+            .unwrap_or(self.entries.len());
+        if rank >= SIZE {
+            return None;
+        }
+
+        self.entries.insert(rank, entry);
+        self.entries.truncate(SIZE);
+        Some(rank + 1)
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    fn sort(&mut self) {
+        self.entries.sort_by_key(|entry| Reverse(entry.score));
+        self.entries.truncate(SIZE);
+    }
+}
+
+impl fmt::Display for Leaderboard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.entries.is_empty() {
+            return writeln!(f, "No scores yet.");
+        }
+
+        writeln!(
+            f,
+            "{:>2}  {:<16} {:>6}  {:<8} {:>8} {:>7}",
+            "#", "Name", "Score", "Level", "Attempts", "Seconds"
+        )?;
+        for (rank, entry) in self.entries.iter().enumerate() {
+            writeln!(
+                f,
+                "{:>2}  {:<16} {:>6}  {:<8} {:>8} {:>7}",
+                rank + 1,
+                entry.name,
+                entry.score,
+                entry.difficulty,
+                entry.attempts,
+                entry.seconds
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn entry(name: &str, score: u32) -> Entry {
+        Entry {
+            name: name.to_string(),
+            score,
+            difficulty: String::from("Normal"),
+            attempts: 4,
+            seconds: 12,
+        }
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("guessing_game-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir.join(name)
+    }
+
+    #[test]
+    fn ranks_by_score() {
+        let mut leaderboard = Leaderboard::default();
+
+        assert_eq!(Some(1), leaderboard.add(entry("Ferris", 300)));
+        assert_eq!(Some(1), leaderboard.add(entry("Corro", 500)));
+        assert_eq!(Some(3), leaderboard.add(entry("Sally", 300)));
+        assert_eq!(None, leaderboard.add(entry(" \t", 900)));
+
+        let names: Vec<&str> = leaderboard
+            .entries()
+            .iter()
+            .map(|entry| entry.name.as_str())
+            .collect();
+        assert_eq!(vec!["Corro", "Ferris", "Sally"], names);
+    }
+
+    #[test]
+    fn keeps_only_the_best() {
+        let mut leaderboard = Leaderboard::default();
+        for score in 1..=SIZE as u32 {
+            leaderboard.add(entry("Player", score * 10));
+        }
+
+        assert_eq!(None, leaderboard.add(entry("Late", 5)));
+        assert_eq!(Some(SIZE), leaderboard.add(entry("Close", 15)));
+        assert_eq!(SIZE, leaderboard.entries().len());
+    }
+
+    #[test]
+    fn save_and_load() {
+        let path = temp_path("save_and_load.txt");
+        let mut leaderboard = Leaderboard::default();
+        leaderboard.add(entry("Amir\tthe\ngreat", 250));
+        leaderboard.add(entry("Sally", 400));
+        leaderboard.save(&path).unwrap();
+
+        let (loaded, skipped) = Leaderboard::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(0, skipped);
+        assert_eq!(leaderboard, loaded);
+        assert_eq!("Amir the great", loaded.entries()[1].name);
+    }
+
+    #[test]
+    fn missing_file_is_empty() {
+        let path = temp_path("missing.txt");
+        let (leaderboard, skipped) = Leaderboard::load(&path).unwrap();
+
+        assert!(leaderboard.entries().is_empty());
+        assert_eq!(0, skipped);
+    }
+
+    #[test]
+    fn recovers_from_corruption() {
+        let path = temp_path("corrupt.txt");
+        let mut contents = format!("{HEADER}\nSally\t400\tHard\t5\t20\n").into_bytes();
+        contents
+            .extend_from_slice(b"\xff\xfe garbage\nAmir\tlots\tEasy\t1\t1\nCorro\t300\tEasy\t2\t");
+        fs::write(&path, contents).unwrap();
+
+        let (leaderboard, skipped) = Leaderboard::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(3, skipped);
+        assert_eq!(1, leaderboard.entries().len());
+        assert_eq!("Sally", leaderboard.entries()[0].name);
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!("No scores yet.\n", Leaderboard::default().to_string());
+
+        let mut leaderboard = Leaderboard::default();
+        leaderboard.add(entry("Sally", 400));
+        assert!(leaderboard
+            .to_string()
+            .ends_with(" 1  Sally               400  Normal          4      12\n"));
+    }
+}
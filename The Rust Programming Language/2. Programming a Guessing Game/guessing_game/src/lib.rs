@@ -0,0 +1,826 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::cmp::Ordering;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+pub mod bulls_and_cows;
+pub mod difficulty;
+pub mod game;
+pub mod guess;
+pub mod leaderboard;
+pub mod network;
+pub mod reverse;
+pub mod score;
+pub mod session;
+pub mod solver;
+pub mod tui;
+pub mod variant;
+pub mod word_game;
+
+use difficulty::Difficulty;
+use game::Game;
+use guess::Guess;
+use leaderboard::{Entry, Leaderboard};
+use network::Mode;
+use score::Puzzle;
+use session::Session;
+use variant::VariantKind;
+
+const LEADERBOARD_FILE: &str = "guessing_game_leaderboard.txt";
+const NETWORK_TIMEOUT: Duration = Duration::from_secs(120);
+
+pub struct Config {
+    pub difficulty: Option<Difficulty>,
+    pub seed: Option<u64>,
+    pub name: Option<String>,
+    pub show_leaderboard: bool,
+    pub leaderboard_file: PathBuf,
+    pub csv_file: Option<PathBuf>,
+    pub host: Option<String>,
+    pub join: Option<String>,
+    pub mode: Mode,
+    pub timeout: Duration,
+    pub auto: bool,
+    pub reverse: bool,
+    pub tui: bool,
+    pub variant: Option<VariantKind>,
+}
+
+impl Config {
+    pub fn build(mut args: impl Iterator<Item = String>) -> Result<Config, &'static str> {
+        args.next();
+
+        let mut preset = None;
+        let mut custom = false;
+        let mut range = None;
+        let mut attempts = None;
+        let mut seed = None;
+        let mut name = None;
+        let mut show_leaderboard = false;
+        let mut leaderboard_file = PathBuf::from(LEADERBOARD_FILE);
+        let mut csv_file = None;
+        let mut host = None;
+        let mut join = None;
+        let mut mode = Mode::Pick;
+        let mut timeout = NETWORK_TIMEOUT;
+        let mut auto = false;
+        let mut reverse = false;
+        let mut tui = false;
+        let mut variant = None;
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--difficulty" => match args.next().as_deref() {
+                    Some("custom") => custom = true,
+                    Some(name) => match Difficulty::parse(name) {
+                        Some(difficulty) => preset = Some(difficulty),
+                        None => return Err("Difficulty must be easy, normal, hard or custom"),
+                    },
+                    None => return Err("Didn't get a difficulty"),
+                },
+                "--range" => match args.next().as_deref().and_then(parse_range) {
+                    Some(bounds) => range = Some(bounds),
+                    None => return Err("Range must look like 1-50"),
+                },
+                "--attempts" => match args.next().and_then(|n| n.parse().ok()) {
+                    Some(n) => attempts = Some(n),
+                    None => return Err("Attempts must be a number"),
+                },
+                "--seed" => match args.next().and_then(|n| n.parse().ok()) {
+                    Some(n) => seed = Some(n),
+                    None => return Err("Seed must be a number"),
+                },
+                "--name" => match args.next() {
+                    Some(arg) => name = Some(arg),
+                    None => return Err("Didn't get a player name"),
+                },
+                "--leaderboard" => show_leaderboard = true,
+                "--leaderboard-file" => match args.next() {
+                    Some(arg) => leaderboard_file = PathBuf::from(arg),
+                    None => return Err("Didn't get a leaderboard file"),
+                },
+                "--csv" => match args.next() {
+                    Some(arg) => csv_file = Some(PathBuf::from(arg)),
+                    None => return Err("Didn't get a CSV file"),
+                },
+                "--host" => match args.next() {
+                    Some(arg) => host = Some(arg),
+                    None => return Err("Didn't get an address to host on"),
+                },
+                "--join" => match args.next() {
+                    Some(arg) => join = Some(arg),
+                    None => return Err("Didn't get an address to join"),
+                },
+                "--mode" => match args.next().as_deref().and_then(Mode::parse) {
+                    Some(arg) => mode = arg,
+                    None => return Err("Mode must be pick or race"),
+                },
+                "--timeout" => match args.next().and_then(|n| n.parse().ok()) {
+                    Some(seconds) => timeout = Duration::from_secs(seconds),
+                    None => return Err("Timeout must be a number of seconds"),
+                },
+                "--auto" => auto = true,
+                "--reverse" => reverse = true,
+                "--tui" => tui = true,
+                "--game" => match args.next().as_deref() {
+                    Some("number") => variant = None,
+                    Some(name) => match VariantKind::parse(name) {
+                        Some(kind) => variant = Some(kind),
+                        None => return Err("Game must be number, bulls or words"),
+                    },
+                    None => return Err("Didn't get a game"),
+                },
+                _ => return Err("Unknown argument"),
+            }
+        }
+
+        if host.is_some() && join.is_some() {
+            return Err("Use either --host or --join, not both");
+        }
+
+        if auto && (host.is_some() || join.is_some()) {
+            return Err("The bot can't play network games");
+        }
+
+        if reverse && (auto || host.is_some() || join.is_some()) {
+            return Err("--reverse can't be combined with --auto, --host or --join");
+        }
+
+        if range.is_some() || attempts.is_some() {
+            custom = true;
+        }
+
+        let difficulty = match (preset, custom) {
+            (Some(_), true) => return Err("--range and --attempts only apply to custom"),
+            (Some(difficulty), false) => Some(difficulty),
+            (None, true) => {
+                let normal = Difficulty::Normal;
+                let (low, high) = range.unwrap_or((*normal.range().start(), *normal.range().end()));
+                let attempts = attempts.unwrap_or(normal.max_attempts());
+                Some(Difficulty::custom(low, high, attempts)?)
+            }
+            (None, false) => None,
+        };
+
+        let number_only = difficulty.is_some() || auto || reverse || tui;
+        if variant.is_some() && (number_only || host.is_some() || join.is_some()) {
+            return Err("Only the number game has difficulties and other modes");
+        }
+
+        Ok(Config {
+            difficulty,
+            seed,
+            name,
+            show_leaderboard,
+            leaderboard_file,
+            csv_file,
+            host,
+            join,
+            mode,
+            timeout,
+            auto,
+            reverse,
+            tui,
+            variant,
+        })
+    }
+}
+
+fn parse_range(range: &str) -> Option<(u32, u32)> {
+    let (low, high) = range.split_once('-')?;
+    Some((low.trim().parse().ok()?, high.trim().parse().ok()?))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Won { attempts: u32 },
+    Lost,
+}
+
+pub fn generate_secret_number(rng: &mut impl Rng, difficulty: &Difficulty) -> u32 {
+    rng.gen_range(difficulty.range())
+}
+
+/// A seeded RNG replays the same secret numbers on every run.
+pub fn make_rng(seed: Option<u64>) -> StdRng {
+    match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    }
+}
+
+/// Reads one line, or `None` once the input has been closed.
+pub(crate) fn read_line(input: &mut impl BufRead) -> io::Result<Option<String>> {
+    let mut line = String::new();
+    if input.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    Ok(Some(line))
+}
+
+fn prompt_number(
+    question: &str,
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+) -> io::Result<Option<u32>> {
+    loop {
+        write!(output, "{question} ")?;
+        output.flush()?;
+
+        let Some(line) = read_line(input)? else {
+            return Ok(None);
+        };
+        match line.trim().parse() {
+            Ok(number) => return Ok(Some(number)),
+            Err(_) => writeln!(output, "Please type a whole number.")?,
+        }
+    }
+}
+
+/// Shows the start menu until the player picks a difficulty, or returns
+/// `None` if the input is closed first.
+pub fn choose_difficulty(
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+) -> io::Result<Option<Difficulty>> {
+    let presets = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard];
+
+    loop {
+        writeln!(output, "Choose a difficulty:")?;
+        for (number, difficulty) in presets.iter().enumerate() {
+            writeln!(output, "{}. {difficulty}", number + 1)?;
+        }
+        writeln!(output, "4. Custom")?;
+
+        let Some(line) = read_line(input)? else {
+            return Ok(None);
+        };
+
+        match line.trim().parse::<usize>() {
+            Ok(choice @ 1..=3) => return Ok(Some(presets[choice - 1])),
+            Ok(4) => {
+                let Some(low) = prompt_number("Lowest number:", input, output)? else {
+                    return Ok(None);
+                };
+                let Some(high) = prompt_number("Highest number:", input, output)? else {
+                    return Ok(None);
+                };
+                let Some(attempts) = prompt_number("Attempts:", input, output)? else {
+                    return Ok(None);
+                };
+
+                match Difficulty::custom(low, high, attempts) {
+                    Ok(difficulty) => return Ok(Some(difficulty)),
+                    Err(err) => writeln!(output, "{err}")?,
+                }
+            }
+            _ => writeln!(output, "Please pick a number from 1 to 4.")?,
+        }
+    }
+}
+
+/// Plays one game. Invalid and repeated guesses are explained and don't use
+/// up an attempt, and neither does typing `hint` to see which numbers are
+/// still possible. Returns `None` if the input is closed mid-game.
+pub fn play(
+    secret_number: u32,
+    difficulty: &Difficulty,
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+) -> io::Result<Option<Outcome>> {
+    let mut game = Game::new(secret_number, difficulty);
+    let range = game.range();
+
+    writeln!(
+        output,
+        "Guess a number between {} and {}. You have {} attempts.",
+        range.start(),
+        range.end(),
+        game.max_attempts()
+    )?;
+    writeln!(output, "Please input your guess (or type hint):")?;
+
+    loop {
+        let Some(line) = read_line(input)? else {
+            return Ok(None);
+        };
+
+        if line.trim().eq_ignore_ascii_case("hint") {
+            writeln!(output, "Hint: {}.", game.interval())?;
+            continue;
+        }
+
+        let (guess, answer) = match game.guess(&line) {
+            Ok(answer) => answer,
+            Err(rejected) => {
+                writeln!(output, "{rejected}")?;
+                continue;
+            }
+        };
+
+        writeln!(output, "You guessed: {guess}")?;
+        match answer {
+            Ordering::Less => writeln!(output, "Too small!")?,
+            Ordering::Greater => writeln!(output, "Too big!")?,
+            Ordering::Equal => writeln!(output, "You win!")?,
+        }
+
+        match game.outcome() {
+            Some(Outcome::Lost) => {
+                writeln!(output, "You lose! The secret number was {secret_number}.")?
+            }
+            Some(Outcome::Won { .. }) => {}
+            None => writeln!(output, "{} attempts left.", game.attempts_left())?,
+        }
+
+        if let Some(outcome) = game.outcome() {
+            return Ok(Some(outcome));
+        }
+    }
+}
+
+/// Asks a winner for their name; an empty answer or closed input skips the
+/// leaderboard.
+fn prompt_name(input: &mut impl BufRead, output: &mut impl Write) -> io::Result<Option<String>> {
+    write!(output, "Enter your name for the leaderboard: ")?;
+    output.flush()?;
+
+    let mut name = String::new();
+    input.read_line(&mut name)?;
+    let name = name.trim();
+
+    Ok(if name.is_empty() {
+        None
+    } else {
+        Some(name.to_string())
+    })
+}
+
+fn prompt_play_again(input: &mut impl BufRead, output: &mut impl Write) -> io::Result<bool> {
+    write!(output, "Play again? (y/n) ")?;
+    output.flush()?;
+
+    let mut answer = String::new();
+    input.read_line(&mut answer)?;
+    let answer = answer.trim().to_lowercase();
+
+    Ok(answer == "y" || answer == "yes")
+}
+
+fn load_leaderboard(config: &Config) -> io::Result<Leaderboard> {
+    let (leaderboard, skipped) = Leaderboard::load(&config.leaderboard_file)?;
+    if skipped > 0 {
+        eprintln!("Ignored {skipped} unreadable leaderboard lines.");
+    }
+    Ok(leaderboard)
+}
+
+fn record_score(
+    config: &Config,
+    puzzle: &Puzzle,
+    attempts: u32,
+    elapsed: Duration,
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+) -> io::Result<()> {
+    let score = puzzle.score(attempts, elapsed);
+    writeln!(output, "Your score: {score}")?;
+
+    let name = match &config.name {
+        Some(name) => name.clone(),
+        None => match prompt_name(input, output)? {
+            Some(name) => name,
+            None => return Ok(()),
+        },
+    };
+
+    let mut leaderboard = load_leaderboard(config)?;
+    let entry = Entry {
+        name,
+        score,
+        difficulty: puzzle.name.to_string(),
+        attempts,
+        seconds: elapsed.as_secs(),
+    };
+
+    if let Some(rank) = leaderboard.add(entry) {
+        leaderboard.save(&config.leaderboard_file)?;
+        writeln!(output, "You made the leaderboard at #{rank}!")?;
+    }
+
+    Ok(())
+}
+
+/// Asks the host of a pick game for the number the other player must guess.
+fn prompt_secret(
+    difficulty: &Difficulty,
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+) -> io::Result<Option<u32>> {
+    let range = difficulty.range();
+
+    loop {
+        writeln!(
+            output,
+            "Pick the secret number, between {} and {}:",
+            range.start(),
+            range.end()
+        )?;
+
+        let Some(line) = read_line(input)? else {
+            return Ok(None);
+        };
+        match Guess::parse(&line, &range) {
+            Ok(guess) => return Ok(Some(guess.value())),
+            Err(err) => writeln!(output, "{err}")?,
+        }
+    }
+}
+
+fn host(
+    config: &Config,
+    address: &str,
+    difficulty: &Difficulty,
+    input: &mut impl BufRead,
+    output: &mut (impl Write + Send),
+) -> io::Result<()> {
+    let secret_number = match config.mode {
+        Mode::Pick => match prompt_secret(difficulty, input, output)? {
+            Some(secret_number) => secret_number,
+            None => return Ok(()),
+        },
+        Mode::Race => generate_secret_number(&mut make_rng(config.seed), difficulty),
+    };
+
+    let listener = TcpListener::bind(address)?;
+    writeln!(
+        output,
+        "Hosting a {} game on {}, waiting for players...",
+        config.mode,
+        listener.local_addr()?
+    )?;
+
+    network::serve(
+        &listener,
+        config.mode,
+        secret_number,
+        difficulty,
+        config.timeout,
+        &mut *output,
+    )?;
+
+    Ok(())
+}
+
+pub fn run(config: Config) -> io::Result<()> {
+    let mut input = io::stdin().lock();
+    let mut output = io::stdout();
+
+    if config.show_leaderboard {
+        write!(output, "{}", load_leaderboard(&config)?)?;
+        return Ok(());
+    }
+
+    writeln!(output, "Guess the number!")?;
+
+    if let Some(address) = &config.join {
+        network::join(address, config.timeout, &mut input, &mut output)?;
+        return Ok(());
+    }
+
+    if let Some(kind) = config.variant {
+        let mut rng = make_rng(config.seed);
+        return play_rounds(&config, &mut input, &mut output, |input, output| {
+            let variant = kind.start(&mut rng);
+            let outcome = variant::play(&*variant, input, output)?;
+
+            if outcome.is_none() {
+                writeln!(output)?;
+                writeln!(output, "Input closed, goodbye!")?;
+            }
+            Ok(outcome.map(|outcome| (outcome, variant.puzzle())))
+        });
+    }
+
+    let difficulty = match config.difficulty {
+        Some(difficulty) => difficulty,
+        None => match choose_difficulty(&mut input, &mut output)? {
+            Some(difficulty) => difficulty,
+            None => return Ok(()),
+        },
+    };
+
+    if let Some(address) = &config.host {
+        return host(&config, address, &difficulty, &mut input, &mut output);
+    }
+
+    if config.reverse {
+        if reverse::play(&difficulty, &mut input, &mut output)?.is_none() {
+            writeln!(output)?;
+            writeln!(output, "Input closed, goodbye!")?;
+        }
+        return Ok(());
+    }
+
+    let mut rng = make_rng(config.seed);
+
+    if config.auto {
+        let secret_number = generate_secret_number(&mut rng, &difficulty);
+        solver::auto_play(secret_number, &difficulty, &mut output)?;
+        return Ok(());
+    }
+
+    // Full screen needs a terminal at both ends; pipes get the plain game.
+    let full_screen = config.tui && io::stdin().is_terminal() && io::stdout().is_terminal();
+    let puzzle = Puzzle::from(&difficulty);
+
+    play_rounds(&config, &mut input, &mut output, |input, output| {
+        let secret_number = generate_secret_number(&mut rng, &difficulty);
+        let outcome = if full_screen {
+            tui::play(secret_number, &difficulty)?
+        } else {
+            play(secret_number, &difficulty, input, output)?
+        };
+
+        if outcome.is_none() {
+            writeln!(output)?;
+            if full_screen {
+                writeln!(output, "Game abandoned, goodbye!")?;
+            } else {
+                writeln!(output, "Input closed, goodbye!")?;
+            }
+        }
+        Ok(outcome.map(|outcome| (outcome, puzzle)))
+    })
+}
+
+/// Plays games until the player stops, scoring wins and keeping session
+/// statistics. `round` plays one game and returns its outcome and what it
+/// is scored on, or `None` if the player left mid-game.
+fn play_rounds<R: BufRead, W: Write>(
+    config: &Config,
+    input: &mut R,
+    output: &mut W,
+    mut round: impl FnMut(&mut R, &mut W) -> io::Result<Option<(Outcome, Puzzle)>>,
+) -> io::Result<()> {
+    let mut session = Session::default();
+
+    loop {
+        let start = Instant::now();
+        let Some((outcome, puzzle)) = round(input, output)? else {
+            break;
+        };
+        session.record(outcome);
+
+        if let Outcome::Won { attempts } = outcome {
+            let elapsed = start.elapsed();
+            record_score(config, &puzzle, attempts, elapsed, input, output)?;
+        }
+
+        if !prompt_play_again(input, output)? {
+            break;
+        }
+    }
+
+    write!(output, "{session}")?;
+    if let Some(path) = &config.csv_file {
+        session.write_csv(path)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(args: &[&str]) -> Result<Config, &'static str> {
+        let args = ["guessing_game"]
+            .iter()
+            .chain(args)
+            .map(|arg| arg.to_string());
+        Config::build(args)
+    }
+
+    fn config_with(args: &[&str]) -> Config {
+        config(args).unwrap()
+    }
+
+    fn play_with(secret_number: u32, difficulty: Difficulty, input: &str) -> (Outcome, String) {
+        let mut output = Vec::new();
+        let outcome = play(
+            secret_number,
+            &difficulty,
+            &mut input.as_bytes(),
+            &mut output,
+        )
+        .unwrap()
+        .expect("the game should finish before the input runs out");
+        (outcome, String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn difficulty_from_args() {
+        assert_eq!(None, config(&[]).unwrap().difficulty);
+        assert_eq!(
+            Some(Difficulty::Hard),
+            config(&["--difficulty", "hard"]).unwrap().difficulty
+        );
+        assert_eq!(
+            Some(Difficulty::Custom {
+                low: 5,
+                high: 20,
+                attempts: 3
+            }),
+            config(&["--range", "5-20", "--attempts", "3"])
+                .unwrap()
+                .difficulty
+        );
+        assert!(config(&["--difficulty", "easy", "--attempts", "3"]).is_err());
+        assert!(config(&["--difficulty", "extreme"]).is_err());
+        assert!(config(&["--range", "20-5"]).is_err());
+    }
+
+    #[test]
+    fn seed_from_args() {
+        assert_eq!(None, config(&[]).unwrap().seed);
+        assert_eq!(Some(42), config(&["--seed", "42"]).unwrap().seed);
+        assert!(config(&["--seed", "-1"]).is_err());
+    }
+
+    #[test]
+    fn seeded_secret_is_reproducible() {
+        let secrets = |seed| {
+            let mut rng = make_rng(Some(seed));
+            (0..5)
+                .map(|_| generate_secret_number(&mut rng, &Difficulty::Hard))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(secrets(7), secrets(7));
+        assert!(secrets(7)
+            .iter()
+            .all(|secret| Difficulty::Hard.range().contains(secret)));
+    }
+
+    #[test]
+    fn leaderboard_from_args() {
+        let config = config(&[]).unwrap();
+        assert!(!config.show_leaderboard);
+        assert_eq!(None, config.name);
+        assert_eq!(PathBuf::from(LEADERBOARD_FILE), config.leaderboard_file);
+
+        let config = config_with(&["--leaderboard", "--leaderboard-file", "scores.txt"]);
+        assert!(config.show_leaderboard);
+        assert_eq!(PathBuf::from("scores.txt"), config.leaderboard_file);
+        assert_eq!(
+            Some(String::from("Sally")),
+            config_with(&["--name", "Sally"]).name
+        );
+    }
+
+    #[test]
+    fn name_prompt() {
+        let mut output = Vec::new();
+        let name = prompt_name(&mut " Sally \n".as_bytes(), &mut output).unwrap();
+        assert_eq!(Some(String::from("Sally")), name);
+
+        assert_eq!(
+            None,
+            prompt_name(&mut "\n".as_bytes(), &mut output).unwrap()
+        );
+        assert_eq!(None, prompt_name(&mut "".as_bytes(), &mut output).unwrap());
+    }
+
+    #[test]
+    fn play_again_prompt() {
+        let mut output = Vec::new();
+
+        assert!(prompt_play_again(&mut "Yes\n".as_bytes(), &mut output).unwrap());
+        assert!(!prompt_play_again(&mut "n\n".as_bytes(), &mut output).unwrap());
+        assert!(!prompt_play_again(&mut "".as_bytes(), &mut output).unwrap());
+        assert_eq!(
+            Some(PathBuf::from("stats.csv")),
+            config_with(&["--csv", "stats.csv"]).csv_file
+        );
+    }
+
+    #[test]
+    fn network_from_args() {
+        let local = config_with(&[]);
+        assert_eq!(None, local.host);
+        assert_eq!(Mode::Pick, local.mode);
+
+        let hosting = config_with(&["--host", "0.0.0.0:7878", "--mode", "race", "--timeout", "5"]);
+        assert_eq!(Some(String::from("0.0.0.0:7878")), hosting.host);
+        assert_eq!(Mode::Race, hosting.mode);
+        assert_eq!(Duration::from_secs(5), hosting.timeout);
+
+        assert!(config(&["--host", "a:1", "--join", "b:2"]).is_err());
+        assert!(config(&["--mode", "duel"]).is_err());
+    }
+
+    #[test]
+    fn secret_prompt() {
+        let mut output = Vec::new();
+        let mut input = "0\nabc\n7\n".as_bytes();
+        let secret = prompt_secret(&Difficulty::Easy, &mut input, &mut output).unwrap();
+
+        assert_eq!(Some(7), secret);
+        assert_eq!(
+            3,
+            String::from_utf8(output)
+                .unwrap()
+                .matches("Pick the")
+                .count()
+        );
+    }
+
+    #[test]
+    fn win() {
+        let (outcome, output) = play_with(7, Difficulty::Easy, "3\nseven\n9\n7\n");
+
+        assert_eq!(Outcome::Won { attempts: 3 }, outcome);
+        assert!(output.contains("Too small!\n4 attempts left."));
+        assert!(output.contains("Too big!\n3 attempts left."));
+        assert!(output.ends_with("You win!\n"));
+    }
+
+    #[test]
+    fn lose_when_attempts_run_out() {
+        let difficulty = Difficulty::custom(1, 10, 2).unwrap();
+        let (outcome, output) = play_with(7, difficulty, "1\n2\n7\n");
+
+        assert_eq!(Outcome::Lost, outcome);
+        assert!(output.ends_with("You lose! The secret number was 7.\n"));
+    }
+
+    #[test]
+    fn start_menu() {
+        let mut output = Vec::new();
+        let mut input = "9\n2\n".as_bytes();
+        let difficulty = choose_difficulty(&mut input, &mut output).unwrap();
+        assert_eq!(Some(Difficulty::Normal), difficulty);
+
+        let mut input = "4\n10\n1\n4\n4\n1\n10\n4\n".as_bytes();
+        let difficulty = choose_difficulty(&mut input, &mut output).unwrap();
+        assert_eq!(Difficulty::custom(1, 10, 4).ok(), difficulty);
+
+        let mut input = "4\n1\n".as_bytes();
+        assert_eq!(None, choose_difficulty(&mut input, &mut output).unwrap());
+    }
+
+    #[test]
+    fn invalid_guesses_are_explained() {
+        let (outcome, output) = play_with(7, Difficulty::Easy, "abc\n\n0\n11\n3\n3\n7\n");
+
+        assert_eq!(Outcome::Won { attempts: 2 }, outcome);
+        assert!(output.contains("'abc' is not a whole number.\n"));
+        assert!(output.contains("Please type a number.\n"));
+        assert!(output.contains("0 is out of range, guess between 1 and 10.\n"));
+        assert!(output.contains("11 is out of range, guess between 1 and 10.\n"));
+        assert!(output.contains("You already guessed 3, try another number.\n"));
+        assert_eq!(2, output.matches("You guessed:").count());
+    }
+
+    #[test]
+    fn hints_show_the_remaining_numbers() {
+        let (outcome, output) = play_with(7, Difficulty::Easy, "hint\n3\n9\nHINT\n7\n");
+
+        assert_eq!(Outcome::Won { attempts: 3 }, outcome);
+        assert!(output.contains("Hint: the number is between 1 and 10.\n"));
+        assert!(output.contains("Hint: the number is between 4 and 8.\n"));
+        assert_eq!(3, output.matches("You guessed:").count());
+    }
+
+    #[test]
+    fn auto_from_args() {
+        assert!(!config_with(&[]).auto);
+        assert!(config_with(&["--auto", "--difficulty", "hard"]).auto);
+        assert!(config(&["--auto", "--join", "a:1"]).is_err());
+
+        assert!(config_with(&["--reverse"]).reverse);
+        assert!(config(&["--reverse", "--auto"]).is_err());
+        assert!(config_with(&["--tui"]).tui);
+
+        assert_eq!(None, config_with(&["--game", "number"]).variant);
+        assert_eq!(
+            Some(VariantKind::Words),
+            config_with(&["--game", "words"]).variant
+        );
+        assert!(config(&["--game", "bulls", "--difficulty", "hard"]).is_err());
+        assert!(config(&["--game", "chess"]).is_err());
+    }
+
+    #[test]
+    fn closed_input_ends_the_game() {
+        let mut output = Vec::new();
+        let mut input = "3\n".as_bytes();
+        let outcome = play(7, &Difficulty::Easy, &mut input, &mut output).unwrap();
+
+        assert_eq!(None, outcome);
+    }
+}
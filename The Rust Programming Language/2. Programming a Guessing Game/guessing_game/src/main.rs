@@ -1,38 +1,16 @@
-use rand::Rng;
-use std::cmp::Ordering;
-use std::io;
+use std::env;
+use std::process;
 
-fn generate_secret_number() -> u32 {
-    rand::thread_rng().gen_range(1..=10)
-}
+use guessing_game::Config;
 
 fn main() {
-    println!("Guess the number!");
-    println!("Please input your guess:");
-
-    let secret_number = generate_secret_number();
-
-    loop {
-        let mut guess = String::new();
-
-        io::stdin()
-            .read_line(&mut guess)
-            .expect("Failed to read line");
-
-        let guess: u32 = match guess.trim().parse() {
-            Ok(num) => num,
-            Err(_) => continue,
-        };
-
-        println!("You guessed: {guess}");
-
-        match guess.cmp(&secret_number) {
-            Ordering::Less => println!("Too small!"),
-            Ordering::Greater => println!("Too big!"),
-            Ordering::Equal => {
-                println!("You win!");
-                break;
-            }
-        }
+    let config = Config::build(env::args()).unwrap_or_else(|err| {
+        eprintln!("Problem parsing arguments: {err}");
+        process::exit(1);
+    });
+
+    if let Err(e) = guessing_game::run(config) {
+        eprintln!("Application error: {e}");
+        process::exit(1);
     }
 }
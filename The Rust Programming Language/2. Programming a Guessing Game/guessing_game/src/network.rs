@@ -0,0 +1,618 @@
+//! Two-player games over TCP.
+//!
+//! The protocol is one message per line. The server greets each player with
+//! `HELLO <mode> <low> <high> <attempts>`, sends `WAIT` while the other
+//! player is missing and `START` once guessing may begin. Players send
+//! `GUESS <text>` or `QUIT`; every guess is answered with `SMALL <left>`,
+//! `BIG <left>`, `INVALID <message>`, `WIN <attempts>` or `LOSE <secret>`.
+//! `END <reason>` closes the game early, for example on a timeout.
+//!
+//! In a race the first correct guess wins, and the other player is sent
+//! `LOSE <secret>` straight away. Their client reads it as the answer to
+//! their next guess.
+
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::difficulty::Difficulty;
+use crate::guess::Guess;
+use crate::{read_line, Outcome};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// The host picks the number and one player guesses it.
+    Pick,
+    /// The server picks the number and two players race to guess it.
+    Race,
+}
+
+impl Mode {
+    pub fn parse(name: &str) -> Option<Mode> {
+        match name {
+            "pick" => Some(Mode::Pick),
+            "race" => Some(Mode::Race),
+            _ => None,
+        }
+    }
+
+    fn players(self) -> usize {
+        match self {
+            Mode::Pick => 1,
+            Mode::Race => 2,
+        }
+    }
+}
+
+impl fmt::Display for Mode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Mode::Pick => write!(f, "pick"),
+            Mode::Race => write!(f, "race"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ServerMessage {
+    Hello {
+        mode: Mode,
+        low: u32,
+        high: u32,
+        attempts: u32,
+    },
+    Wait,
+    Start,
+    TooSmall {
+        left: u32,
+    },
+    TooBig {
+        left: u32,
+    },
+    Invalid(String),
+    Win {
+        attempts: u32,
+    },
+    Lose {
+        secret: u32,
+    },
+    End(String),
+}
+
+impl ServerMessage {
+    pub fn parse(line: &str) -> Option<ServerMessage> {
+        let line = line.trim_end();
+        let (command, rest) = line.split_once(' ').unwrap_or((line, ""));
+        let number = |text: &str| text.parse().ok();
+
+        let message = match command {
+            "HELLO" => {
+                let mut fields = rest.split(' ');
+                let message = ServerMessage::Hello {
+                    mode: Mode::parse(fields.next()?)?,
+                    low: number(fields.next()?)?,
+                    high: number(fields.next()?)?,
+                    attempts: number(fields.next()?)?,
+                };
+                if fields.next().is_some() {
+                    return None;
+                }
+                message
+            }
+            "WAIT" => ServerMessage::Wait,
+            "START" => ServerMessage::Start,
+            "SMALL" => ServerMessage::TooSmall {
+                left: number(rest)?,
+            },
+            "BIG" => ServerMessage::TooBig {
+                left: number(rest)?,
+            },
+            "INVALID" => ServerMessage::Invalid(rest.to_string()),
+            "WIN" => ServerMessage::Win {
+                attempts: number(rest)?,
+            },
+            "LOSE" => ServerMessage::Lose {
+                secret: number(rest)?,
+            },
+            "END" => ServerMessage::End(rest.to_string()),
+            _ => return None,
+        };
+        Some(message)
+    }
+}
+
+impl fmt::Display for ServerMessage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ServerMessage::Hello {
+                mode,
+                low,
+                high,
+                attempts,
+            } => write!(f, "HELLO {mode} {low} {high} {attempts}"),
+            ServerMessage::Wait => write!(f, "WAIT"),
+            ServerMessage::Start => write!(f, "START"),
+            ServerMessage::TooSmall { left } => write!(f, "SMALL {left}"),
+            ServerMessage::TooBig { left } => write!(f, "BIG {left}"),
+            ServerMessage::Invalid(message) => write!(f, "INVALID {message}"),
+            ServerMessage::Win { attempts } => write!(f, "WIN {attempts}"),
+            ServerMessage::Lose { secret } => write!(f, "LOSE {secret}"),
+            ServerMessage::End(reason) => write!(f, "END {reason}"),
+        }
+    }
+}
+
+/// How a remote player's game finished, as seen by the server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlayerResult {
+    Won { attempts: u32 },
+    Lost,
+    Quit,
+    Disconnected,
+    TimedOut,
+}
+
+/// How often the server checks on players while it waits for them.
+const POLL: Duration = Duration::from_millis(50);
+
+fn send(stream: &Mutex<TcpStream>, message: &ServerMessage) -> io::Result<()> {
+    writeln!(stream.lock().unwrap(), "{message}")
+}
+
+/// Whether the other end of `stream` has closed it. Anything it already sent
+/// is left unread.
+fn has_closed(stream: &TcpStream) -> io::Result<bool> {
+    stream.set_nonblocking(true)?;
+    let closed = match stream.peek(&mut [0]) {
+        Ok(read) => read == 0,
+        Err(e) if e.kind() == io::ErrorKind::WouldBlock => false,
+        Err(e) if e.kind() == io::ErrorKind::ConnectionReset => true,
+        Err(e) => return Err(e),
+    };
+    stream.set_nonblocking(false)?;
+    Ok(closed)
+}
+
+fn log(log: &Mutex<impl Write>, message: fmt::Arguments) {
+    let mut log = log.lock().unwrap();
+    let _ = log.write_fmt(message);
+    let _ = writeln!(log);
+}
+
+/// Accepts the players `mode` needs on `listener`, then referees their game
+/// against `secret_number`. A player who sends nothing for `timeout` is
+/// dropped, and if the players haven't all joined after `timeout` the game
+/// is called off. Progress is written to `log`; the result for each player
+/// who was still there when the game started or was called off is returned
+/// in the order they connected.
+pub fn serve(
+    listener: &TcpListener,
+    mode: Mode,
+    secret_number: u32,
+    difficulty: &Difficulty,
+    timeout: Duration,
+    log_output: impl Write + Send,
+) -> io::Result<Vec<PlayerResult>> {
+    let log_output = Mutex::new(log_output);
+    let streams = accept_players(listener, mode, difficulty, timeout, &log_output)?;
+    if streams.len() < mode.players() {
+        return Ok(vec![PlayerResult::TimedOut; streams.len()]);
+    }
+
+    for stream in &streams {
+        send(stream, &ServerMessage::Start)?;
+    }
+
+    let winner = Mutex::new(None);
+
+    thread::scope(|scope| {
+        let handles: Vec<_> = (0..streams.len())
+            .map(|index| {
+                let player = Player {
+                    number: index + 1,
+                    secret_number,
+                    difficulty,
+                    timeout,
+                    streams: &streams,
+                    winner: &winner,
+                    log: &log_output,
+                };
+                scope.spawn(move || player.referee())
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect()
+    })
+}
+
+/// Waits up to `timeout` for the players `mode` needs, dropping any who
+/// leave while they wait. If they don't all turn up, the ones who did are
+/// told the game is off and fewer streams are returned.
+fn accept_players(
+    listener: &TcpListener,
+    mode: Mode,
+    difficulty: &Difficulty,
+    timeout: Duration,
+    log_output: &Mutex<impl Write>,
+) -> io::Result<Vec<Mutex<TcpStream>>> {
+    let range = difficulty.range();
+    let hello = ServerMessage::Hello {
+        mode,
+        low: *range.start(),
+        high: *range.end(),
+        attempts: difficulty.max_attempts(),
+    };
+
+    let deadline = Instant::now() + timeout;
+    let mut streams: Vec<Mutex<TcpStream>> = Vec::new();
+
+    listener.set_nonblocking(true)?;
+    let accepted = loop {
+        if streams.len() == mode.players() {
+            break Ok(true);
+        }
+
+        match listener.accept() {
+            Ok((stream, address)) => {
+                stream.set_nonblocking(false)?;
+                stream.set_read_timeout(Some(POLL.min(timeout)))?;
+                let stream = Mutex::new(stream);
+                send(&stream, &hello)?;
+                streams.push(stream);
+
+                log(
+                    log_output,
+                    format_args!("Player {} connected from {address}.", streams.len()),
+                );
+                if streams.len() < mode.players() {
+                    send(streams.last().unwrap(), &ServerMessage::Wait)?;
+                }
+            }
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                if Instant::now() >= deadline {
+                    break Ok(false);
+                }
+                let before = streams.len();
+                streams.retain(|stream| !has_closed(&stream.lock().unwrap()).unwrap_or(true));
+                if streams.len() < before {
+                    log(
+                        log_output,
+                        format_args!("A waiting player left, {} still waiting.", streams.len()),
+                    );
+                }
+                thread::sleep(POLL);
+            }
+            Err(e) => break Err(e),
+        }
+    };
+    listener.set_nonblocking(false)?;
+
+    if !accepted? {
+        log(
+            log_output,
+            format_args!("Not enough players joined in time."),
+        );
+        for stream in &streams {
+            let _ = send(
+                stream,
+                &ServerMessage::End(String::from("no one else joined")),
+            );
+        }
+    }
+    Ok(streams)
+}
+
+struct Player<'a, W> {
+    number: usize,
+    secret_number: u32,
+    difficulty: &'a Difficulty,
+    timeout: Duration,
+    /// Every player's connection, so the winner can tell the others.
+    streams: &'a [Mutex<TcpStream>],
+    winner: &'a Mutex<Option<usize>>,
+    log: &'a Mutex<W>,
+}
+
+impl<W: Write> Player<'_, W> {
+    fn stream(&self) -> &Mutex<TcpStream> {
+        &self.streams[self.number - 1]
+    }
+
+    fn send(&self, message: &ServerMessage) -> io::Result<()> {
+        send(self.stream(), message)
+    }
+
+    fn someone_won(&self) -> bool {
+        self.winner.lock().unwrap().is_some()
+    }
+
+    /// Claims the win, unless another player got there first.
+    fn claim_win(&self) -> bool {
+        let mut winner = self.winner.lock().unwrap();
+        if winner.is_some() {
+            return false;
+        }
+        *winner = Some(self.number);
+        true
+    }
+
+    fn referee(&self) -> io::Result<PlayerResult> {
+        let mut reader = BufReader::new(self.stream().lock().unwrap().try_clone()?);
+        let range = self.difficulty.range();
+        let max_attempts = self.difficulty.max_attempts();
+        let mut attempts = 0;
+        let mut guessed = HashSet::new();
+        let mut line = String::new();
+        let mut last_heard = Instant::now();
+
+        loop {
+            // Reads time out every `POLL` so a player waiting on their next
+            // guess still notices that someone else has won.
+            line.clear();
+            let read = loop {
+                match reader.read_line(&mut line) {
+                    Ok(read) => break read,
+                    Err(e) if timed_out(&e) => {
+                        if self.someone_won() {
+                            return Ok(PlayerResult::Lost);
+                        }
+                        if last_heard.elapsed() >= self.timeout {
+                            log(self.log, format_args!("Player {} timed out.", self.number));
+                            let _ = self.send(&ServerMessage::End(String::from("timed out")));
+                            return Ok(PlayerResult::TimedOut);
+                        }
+                    }
+                    Err(e) => return Err(e),
+                }
+            };
+            if read == 0 {
+                log(
+                    self.log,
+                    format_args!("Player {} disconnected.", self.number),
+                );
+                return Ok(PlayerResult::Disconnected);
+            }
+            last_heard = Instant::now();
+
+            let line = line.trim();
+            if line == "QUIT" {
+                log(self.log, format_args!("Player {} quit.", self.number));
+                return Ok(PlayerResult::Quit);
+            }
+            let Some(text) = line.strip_prefix("GUESS ") else {
+                self.send(&ServerMessage::Invalid(String::from(
+                    "Expected GUESS or QUIT.",
+                )))?;
+                continue;
+            };
+
+            // The winner has already sent this player `LOSE`, which answers
+            // the guess.
+            if self.someone_won() {
+                return Ok(PlayerResult::Lost);
+            }
+
+            let guess = match Guess::parse(text, &range) {
+                Ok(guess) => guess.value(),
+                Err(err) => {
+                    self.send(&ServerMessage::Invalid(err.to_string()))?;
+                    continue;
+                }
+            };
+            if !guessed.insert(guess) {
+                let message = format!("You already guessed {guess}, try another number.");
+                self.send(&ServerMessage::Invalid(message))?;
+                continue;
+            }
+
+            attempts += 1;
+            let left = max_attempts - attempts;
+            log(
+                self.log,
+                format_args!("Player {} guessed {guess}.", self.number),
+            );
+
+            let reply = match guess.cmp(&self.secret_number) {
+                Ordering::Equal if self.claim_win() => {
+                    log(self.log, format_args!("Player {} wins!", self.number));
+                    self.send(&ServerMessage::Win { attempts })?;
+                    self.tell_the_others();
+                    return Ok(PlayerResult::Won { attempts });
+                }
+                // Someone else won while this guess was being checked, and
+                // has sent this player `LOSE`.
+                Ordering::Equal => return Ok(PlayerResult::Lost),
+                _ if left == 0 => {
+                    log(
+                        self.log,
+                        format_args!("Player {} is out of attempts.", self.number),
+                    );
+                    self.send(&ServerMessage::Lose {
+                        secret: self.secret_number,
+                    })?;
+                    return Ok(PlayerResult::Lost);
+                }
+                Ordering::Less => ServerMessage::TooSmall { left },
+                Ordering::Greater => ServerMessage::TooBig { left },
+            };
+            self.send(&reply)?;
+        }
+    }
+
+    /// Sends `LOSE` to every other player. Players who already left are
+    /// skipped.
+    fn tell_the_others(&self) {
+        let lose = ServerMessage::Lose {
+            secret: self.secret_number,
+        };
+        for (index, stream) in self.streams.iter().enumerate() {
+            if index + 1 != self.number {
+                let _ = send(stream, &lose);
+            }
+        }
+    }
+}
+
+/// Whether a read gave up because its timeout ran out, which shows up as
+/// either error kind depending on the platform.
+fn timed_out(e: &io::Error) -> bool {
+    e.kind() == io::ErrorKind::WouldBlock || e.kind() == io::ErrorKind::TimedOut
+}
+
+/// Reads the next message. A server that sends nothing for the read timeout
+/// has stalled, which ends the game just as an `END` would.
+fn receive(reader: &mut impl BufRead) -> io::Result<ServerMessage> {
+    let line = match read_line(reader) {
+        Ok(line) => line,
+        Err(e) if timed_out(&e) => {
+            return Ok(ServerMessage::End(String::from(
+                "the server stopped responding",
+            )))
+        }
+        Err(e) => return Err(e),
+    };
+    let Some(line) = line else {
+        return Err(io::Error::new(
+            io::ErrorKind::ConnectionAborted,
+            "the server closed the connection",
+        ));
+    };
+    ServerMessage::parse(&line).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("unexpected message from the server: {}", line.trim_end()),
+        )
+    })
+}
+
+/// Plays as a guesser against the server at `address`, reading guesses from
+/// `input`. Returns `None` if the game ended without a result, because the
+/// player's input closed, the server ended it or the server sent nothing for
+/// `timeout` while an answer was due.
+pub fn join(
+    address: &str,
+    timeout: Duration,
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+) -> io::Result<Option<Outcome>> {
+    let stream = TcpStream::connect(address)?;
+    stream.set_read_timeout(Some(timeout))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut stream = stream;
+
+    loop {
+        match receive(&mut reader)? {
+            ServerMessage::Hello {
+                mode,
+                low,
+                high,
+                attempts,
+            } => {
+                let goal = match mode {
+                    Mode::Pick => "The host picked a number",
+                    Mode::Race => "Race the other player to guess a number",
+                };
+                writeln!(output, "{goal} between {low} and {high}.")?;
+                writeln!(output, "You have {attempts} attempts.")?;
+            }
+            ServerMessage::Wait => writeln!(output, "Waiting for the other player...")?,
+            ServerMessage::Start => break,
+            ServerMessage::End(reason) => {
+                writeln!(output, "Game over: {reason}.")?;
+                return Ok(None);
+            }
+            message => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("unexpected message before the game started: {message}"),
+                ))
+            }
+        }
+    }
+
+    writeln!(output, "Please input your guess:")?;
+
+    loop {
+        let Some(line) = read_line(input)? else {
+            writeln!(stream, "QUIT")?;
+            return Ok(None);
+        };
+        writeln!(stream, "GUESS {}", line.trim())?;
+
+        match receive(&mut reader)? {
+            ServerMessage::TooSmall { left } => {
+                writeln!(output, "Too small!")?;
+                writeln!(output, "{left} attempts left.")?;
+            }
+            ServerMessage::TooBig { left } => {
+                writeln!(output, "Too big!")?;
+                writeln!(output, "{left} attempts left.")?;
+            }
+            ServerMessage::Invalid(message) => writeln!(output, "{message}")?,
+            ServerMessage::Win { attempts } => {
+                writeln!(output, "You win!")?;
+                return Ok(Some(Outcome::Won { attempts }));
+            }
+            ServerMessage::Lose { secret } => {
+                writeln!(output, "You lose! The secret number was {secret}.")?;
+                return Ok(Some(Outcome::Lost));
+            }
+            ServerMessage::End(reason) => {
+                writeln!(output, "Game over: {reason}.")?;
+                return Ok(None);
+            }
+            message => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("unexpected reply to a guess: {message}"),
+                ))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn messages_round_trip() {
+        let messages = [
+            ServerMessage::Hello {
+                mode: Mode::Race,
+                low: 1,
+                high: 100,
+                attempts: 7,
+            },
+            ServerMessage::Wait,
+            ServerMessage::Start,
+            ServerMessage::TooSmall { left: 3 },
+            ServerMessage::TooBig { left: 0 },
+            ServerMessage::Invalid(String::from("'x' is not a whole number.")),
+            ServerMessage::Win { attempts: 4 },
+            ServerMessage::Lose { secret: 42 },
+            ServerMessage::End(String::from("timed out")),
+        ];
+
+        for message in messages {
+            let line = format!("{message}\n");
+            assert_eq!(Some(message), ServerMessage::parse(&line));
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_messages() {
+        assert_eq!(None, ServerMessage::parse("HELLO race 1 100"));
+        assert_eq!(None, ServerMessage::parse("HELLO duel 1 100 7"));
+        assert_eq!(None, ServerMessage::parse("SMALL lots"));
+        assert_eq!(None, ServerMessage::parse("CHEAT 42"));
+    }
+}
@@ -0,0 +1,149 @@
+//! The reverse game: the player thinks of a number and the computer guesses
+//! it, narrowing the range with each `higher` or `lower` answer.
+
+use std::cmp::Ordering;
+use std::io::{self, BufRead, Write};
+
+use crate::difficulty::Difficulty;
+use crate::read_line;
+use crate::solver::Interval;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Answer {
+    Higher,
+    Lower,
+    Correct,
+}
+
+impl Answer {
+    pub fn parse(answer: &str) -> Option<Answer> {
+        match answer.trim().to_lowercase().as_str() {
+            "higher" | "h" => Some(Answer::Higher),
+            "lower" | "l" => Some(Answer::Lower),
+            "correct" | "c" => Some(Answer::Correct),
+            _ => None,
+        }
+    }
+
+    /// The answer as `guess.cmp(&secret)`: "higher" means the guess was too
+    /// small.
+    fn ordering(self) -> Ordering {
+        match self {
+            Answer::Higher => Ordering::Less,
+            Answer::Lower => Ordering::Greater,
+            Answer::Correct => Ordering::Equal,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReverseOutcome {
+    Guessed {
+        attempts: u32,
+    },
+    /// The answers contradict each other, so no number could fit them all.
+    Cheated,
+}
+
+/// Guesses the player's number by binary search. Returns `None` if the input
+/// is closed mid-game.
+pub fn play(
+    difficulty: &Difficulty,
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+) -> io::Result<Option<ReverseOutcome>> {
+    let range = difficulty.range();
+    writeln!(
+        output,
+        "Think of a number between {} and {}, and I'll guess it.",
+        range.start(),
+        range.end()
+    )?;
+    writeln!(output, "Answer each guess with higher, lower or correct.")?;
+
+    let mut interval = Interval::new(&range);
+    let mut attempts = 0;
+
+    while let Some(guess) = interval.midpoint() {
+        attempts += 1;
+        writeln!(output, "Is it {guess}?")?;
+
+        let answer = loop {
+            let Some(line) = read_line(input)? else {
+                return Ok(None);
+            };
+            match Answer::parse(&line) {
+                Some(answer) => break answer,
+                None => writeln!(output, "Please answer higher, lower or correct.")?,
+            }
+        };
+
+        if answer == Answer::Correct {
+            writeln!(output, "Got it in {attempts} guesses!")?;
+            return Ok(Some(ReverseOutcome::Guessed { attempts }));
+        }
+        interval.narrow(guess, answer.ordering());
+    }
+
+    writeln!(
+        output,
+        "You're cheating! No number between {} and {} fits your answers.",
+        range.start(),
+        range.end()
+    )?;
+    Ok(Some(ReverseOutcome::Cheated))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn play_with(difficulty: Difficulty, input: &str) -> (Option<ReverseOutcome>, String) {
+        let mut output = Vec::new();
+        let outcome = play(&difficulty, &mut input.as_bytes(), &mut output).unwrap();
+        (outcome, String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn parse_answers() {
+        assert_eq!(Some(Answer::Higher), Answer::parse(" Higher\n"));
+        assert_eq!(Some(Answer::Lower), Answer::parse("l"));
+        assert_eq!(Some(Answer::Correct), Answer::parse("correct"));
+        assert_eq!(None, Answer::parse("maybe"));
+    }
+
+    #[test]
+    fn guesses_the_number() {
+        let (outcome, output) = play_with(Difficulty::Normal, "higher\nlower\nwhat\ncorrect\n");
+
+        assert_eq!(Some(ReverseOutcome::Guessed { attempts: 3 }), outcome);
+        assert!(output.contains("Is it 50?\nIs it 75?\nIs it 62?\n"));
+        assert!(output.contains("Please answer higher, lower or correct.\n"));
+        assert!(output.ends_with("Got it in 3 guesses!\n"));
+    }
+
+    #[test]
+    fn catches_contradicting_answers() {
+        // Higher than 5 and lower than 8 leaves 6 and 7; denying both is a lie.
+        let (outcome, output) = play_with(Difficulty::Easy, "h\nl\nh\nh\n");
+
+        assert_eq!(Some(ReverseOutcome::Cheated), outcome);
+        assert!(output.contains("Is it 5?\nIs it 8?\nIs it 6?\nIs it 7?\n"));
+        assert!(
+            output.ends_with("You're cheating! No number between 1 and 10 fits your answers.\n")
+        );
+    }
+
+    #[test]
+    fn lowest_number_cannot_be_lower() {
+        let difficulty = Difficulty::custom(0, 1, 5).unwrap();
+        let (outcome, _) = play_with(difficulty, "lower\n");
+
+        assert_eq!(Some(ReverseOutcome::Cheated), outcome);
+    }
+
+    #[test]
+    fn closed_input() {
+        assert_eq!(None, play_with(Difficulty::Easy, "higher\n").0);
+    }
+}
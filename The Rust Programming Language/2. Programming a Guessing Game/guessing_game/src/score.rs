@@ -0,0 +1,99 @@
+use std::time::Duration;
+
+use crate::difficulty::Difficulty;
+
+/// What a won game is scored and ranked on, whichever game it was.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Puzzle {
+    /// Shown in the leaderboard's difficulty column.
+    pub name: &'static str,
+    /// How many secrets the game could have picked.
+    pub possibilities: u64,
+    pub max_attempts: u32,
+}
+
+impl Puzzle {
+    /// Points for a won game. More possible secrets are worth more, every
+    /// unused attempt adds a share of that, and each second spent guessing
+    /// costs a point.
+    pub fn score(&self, attempts: u32, elapsed: Duration) -> u32 {
+        // Guesses a binary search needs in the worst case, at least one.
+        let bits = (u64::BITS - self.possibilities.saturating_sub(1).leading_zeros()).max(1);
+
+        // Worked out in u64 so a huge custom attempt limit can't overflow.
+        let max_attempts = u64::from(self.max_attempts.max(1));
+        let unused = max_attempts.saturating_sub(u64::from(attempts)) + 1;
+        let base = u32::try_from(100 * u64::from(bits) * unused / max_attempts).unwrap_or(u32::MAX);
+
+        let seconds = u32::try_from(elapsed.as_secs()).unwrap_or(u32::MAX);
+        base.saturating_sub(seconds).max(1)
+    }
+}
+
+impl From<&Difficulty> for Puzzle {
+    fn from(difficulty: &Difficulty) -> Puzzle {
+        let range = difficulty.range();
+        Puzzle {
+            name: difficulty.name(),
+            possibilities: u64::from(range.end() - range.start()) + 1,
+            max_attempts: difficulty.max_attempts(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fewer_attempts_score_more() {
+        let quick = Puzzle::from(&Difficulty::Normal).score(1, Duration::ZERO);
+        let slow = Puzzle::from(&Difficulty::Normal).score(7, Duration::ZERO);
+
+        assert_eq!(700, quick);
+        assert_eq!(100, slow);
+    }
+
+    #[test]
+    fn harder_scores_more() {
+        let easy = Puzzle::from(&Difficulty::Easy).score(3, Duration::ZERO);
+        let hard = Puzzle::from(&Difficulty::Hard).score(3, Duration::ZERO);
+
+        assert!(hard > easy);
+    }
+
+    #[test]
+    fn time_costs_points() {
+        let elapsed = Duration::from_secs(30);
+        assert_eq!(670, Puzzle::from(&Difficulty::Normal).score(1, elapsed));
+
+        let forever = Duration::from_secs(u64::MAX);
+        assert_eq!(1, Puzzle::from(&Difficulty::Normal).score(1, forever));
+    }
+
+    #[test]
+    fn single_number_range() {
+        let difficulty = Difficulty::custom(5, 5, 1).unwrap();
+        assert_eq!(100, Puzzle::from(&difficulty).score(1, Duration::ZERO));
+    }
+
+    #[test]
+    fn huge_attempt_limit() {
+        let difficulty = Difficulty::custom(1, 100, 10_000_000).unwrap();
+        assert_eq!(700, Puzzle::from(&difficulty).score(1, Duration::ZERO));
+
+        let difficulty = Difficulty::custom(1, 100, u32::MAX).unwrap();
+        assert_eq!(700, Puzzle::from(&difficulty).score(1, Duration::ZERO));
+    }
+
+    #[test]
+    fn puzzles_score_on_possibilities() {
+        let puzzle = Puzzle {
+            name: "Bulls and cows",
+            possibilities: 5040,
+            max_attempts: 10,
+        };
+        assert_eq!(1300, puzzle.score(1, Duration::ZERO));
+        assert_eq!(130, puzzle.score(10, Duration::ZERO));
+    }
+}
@@ -0,0 +1,186 @@
+use std::collections::BTreeMap;
+use std::fmt::{self, Write as _};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::Outcome;
+
+/// Every game played since the program started.
+#[derive(Debug, Default)]
+pub struct Session {
+    outcomes: Vec<Outcome>,
+}
+
+impl Session {
+    pub fn record(&mut self, outcome: Outcome) {
+        self.outcomes.push(outcome);
+    }
+
+    pub fn games_played(&self) -> usize {
+        self.outcomes.len()
+    }
+
+    pub fn wins(&self) -> usize {
+        self.guesses_to_win().count()
+    }
+
+    pub fn win_rate(&self) -> Option<f64> {
+        if self.outcomes.is_empty() {
+            return None;
+        }
+        Some(self.wins() as f64 / self.games_played() as f64)
+    }
+
+    pub fn average_guesses(&self) -> Option<f64> {
+        if self.wins() == 0 {
+            return None;
+        }
+        let total: u32 = self.guesses_to_win().sum();
+        Some(f64::from(total) / self.wins() as f64)
+    }
+
+    pub fn best_guesses(&self) -> Option<u32> {
+        self.guesses_to_win().min()
+    }
+
+    /// How many games were won in each number of guesses, with no gaps
+    /// between the best and the worst win.
+    pub fn histogram(&self) -> BTreeMap<u32, usize> {
+        let mut histogram = BTreeMap::new();
+        if let (Some(best), Some(worst)) = (self.best_guesses(), self.guesses_to_win().max()) {
+            for guesses in best..=worst {
+                histogram.insert(guesses, 0);
+            }
+        }
+        for guesses in self.guesses_to_win() {
+            *histogram.entry(guesses).or_insert(0) += 1;
+        }
+        histogram
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("statistic,value\n");
+        let _ = writeln!(csv, "games_played,{}", self.games_played());
+        let _ = writeln!(csv, "wins,{}", self.wins());
+        let _ = writeln!(csv, "win_rate,{}", optional(self.win_rate()));
+        let _ = writeln!(csv, "average_guesses,{}", optional(self.average_guesses()));
+        let _ = writeln!(csv, "best_guesses,{}", optional(self.best_guesses()));
+        for (guesses, count) in self.histogram() {
+            let _ = writeln!(csv, "wins_in_{guesses}_guesses,{count}");
+        }
+        csv
+    }
+
+    pub fn write_csv(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_csv())
+    }
+
+    fn guesses_to_win(&self) -> impl Iterator<Item = u32> + '_ {
+        self.outcomes.iter().filter_map(|outcome| match outcome {
+            Outcome::Won { attempts } => Some(*attempts),
+            Outcome::Lost => None,
+        })
+    }
+}
+
+fn optional<T: fmt::Display>(value: Option<T>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_default()
+}
+
+impl fmt::Display for Session {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Session statistics:")?;
+        writeln!(f, "Games played: {}", self.games_played())?;
+
+        if let Some(rate) = self.win_rate() {
+            writeln!(f, "Win rate: {:.1}%", rate * 100.0)?;
+        }
+        if let (Some(average), Some(best)) = (self.average_guesses(), self.best_guesses()) {
+            writeln!(f, "Average guesses to win: {average:.1}")?;
+            writeln!(f, "Best guesses to win: {best}")?;
+            writeln!(f, "Guesses to win:")?;
+            for (guesses, count) in self.histogram() {
+                writeln!(f, "{guesses:>3} | {} ({count})", "#".repeat(count))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(outcomes: &[Outcome]) -> Session {
+        let mut session = Session::default();
+        for &outcome in outcomes {
+            session.record(outcome);
+        }
+        session
+    }
+
+    #[test]
+    fn empty() {
+        let session = Session::default();
+
+        assert_eq!(None, session.win_rate());
+        assert_eq!(None, session.average_guesses());
+        assert!(session.histogram().is_empty());
+        assert_eq!(
+            "Session statistics:\nGames played: 0\n",
+            session.to_string()
+        );
+    }
+
+    #[test]
+    fn statistics() {
+        let session = session(&[
+            Outcome::Won { attempts: 5 },
+            Outcome::Lost,
+            Outcome::Won { attempts: 3 },
+            Outcome::Won { attempts: 5 },
+        ]);
+
+        assert_eq!(4, session.games_played());
+        assert_eq!(3, session.wins());
+        assert_eq!(Some(0.75), session.win_rate());
+        assert_eq!(Some(13.0 / 3.0), session.average_guesses());
+        assert_eq!(Some(3), session.best_guesses());
+        assert_eq!(
+            BTreeMap::from([(3, 1), (4, 0), (5, 2)]),
+            session.histogram()
+        );
+        assert!(session.to_string().ends_with(
+            "\
+Win rate: 75.0%
+Average guesses to win: 4.3
+Best guesses to win: 3
+Guesses to win:
+  3 | # (1)
+  4 |  (0)
+  5 | ## (2)
+"
+        ));
+    }
+
+    #[test]
+    fn csv() {
+        let session = session(&[Outcome::Lost, Outcome::Won { attempts: 2 }]);
+
+        assert_eq!(
+            "\
+statistic,value
+games_played,2
+wins,1
+win_rate,0.5
+average_guesses,2
+best_guesses,2
+wins_in_2_guesses,1
+",
+            session.to_csv()
+        );
+        assert!(Session::default().to_csv().contains("average_guesses,\n"));
+    }
+}
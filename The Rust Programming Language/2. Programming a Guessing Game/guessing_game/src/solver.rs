@@ -0,0 +1,202 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::io::{self, Write};
+use std::ops::RangeInclusive;
+
+use crate::difficulty::Difficulty;
+use crate::Outcome;
+
+/// The numbers that could still be the secret, given the answers so far.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Interval {
+    low: u32,
+    high: u32,
+    empty: bool,
+}
+
+impl Interval {
+    pub fn new(range: &RangeInclusive<u32>) -> Interval {
+        Interval {
+            low: *range.start(),
+            high: *range.end(),
+            empty: range.is_empty(),
+        }
+    }
+
+    /// Narrows the interval with the answer to a guess, where `answer` is
+    /// `guess.cmp(&secret)`: `Less` means "too small", `Greater` "too big".
+    pub fn narrow(&mut self, guess: u32, answer: Ordering) {
+        match answer {
+            Ordering::Less => match guess.checked_add(1) {
+                Some(low) => self.low = self.low.max(low),
+                None => self.empty = true,
+            },
+            Ordering::Greater => match guess.checked_sub(1) {
+                Some(high) => self.high = self.high.min(high),
+                None => self.empty = true,
+            },
+            Ordering::Equal => {
+                if !self.contains(guess) {
+                    self.empty = true;
+                }
+                self.low = guess;
+                self.high = guess;
+            }
+        }
+
+        if self.low > self.high {
+            self.empty = true;
+        }
+    }
+
+    pub fn low(&self) -> u32 {
+        self.low
+    }
+
+    pub fn high(&self) -> u32 {
+        self.high
+    }
+
+    /// No number fits every answer, so one of them must have been wrong.
+    pub fn is_empty(&self) -> bool {
+        self.empty
+    }
+
+    pub fn contains(&self, number: u32) -> bool {
+        !self.empty && (self.low..=self.high).contains(&number)
+    }
+
+    /// How many numbers are still possible.
+    pub fn len(&self) -> u64 {
+        if self.empty {
+            0
+        } else {
+            u64::from(self.high - self.low) + 1
+        }
+    }
+
+    /// The guess that halves the interval, which is never more than
+    /// `log2(len) + 1` guesses away from the secret.
+    pub fn midpoint(&self) -> Option<u32> {
+        if self.empty {
+            None
+        } else {
+            Some(self.low + (self.high - self.low) / 2)
+        }
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.empty {
+            write!(f, "no number fits the answers so far")
+        } else if self.low == self.high {
+            write!(f, "the number must be {}", self.low)
+        } else {
+            write!(f, "the number is between {} and {}", self.low, self.high)
+        }
+    }
+}
+
+/// Lets the bot play a game by binary search, printing each guess.
+pub fn auto_play(
+    secret_number: u32,
+    difficulty: &Difficulty,
+    output: &mut impl Write,
+) -> io::Result<Outcome> {
+    let max_attempts = difficulty.max_attempts();
+    let mut interval = Interval::new(&difficulty.range());
+    let mut attempts = 0;
+
+    while let Some(guess) = interval.midpoint() {
+        attempts += 1;
+        writeln!(output, "Bot guesses: {guess}")?;
+
+        let answer = guess.cmp(&secret_number);
+        match answer {
+            Ordering::Less => writeln!(output, "Too small!")?,
+            Ordering::Greater => writeln!(output, "Too big!")?,
+            Ordering::Equal => {
+                writeln!(output, "The bot needed {attempts} guesses.")?;
+                return Ok(Outcome::Won { attempts });
+            }
+        }
+
+        if attempts == max_attempts {
+            break;
+        }
+        interval.narrow(guess, answer);
+    }
+
+    writeln!(
+        output,
+        "The bot ran out of attempts! The secret number was {secret_number}."
+    )?;
+    Ok(Outcome::Lost)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn narrowing() {
+        let mut interval = Interval::new(&(1..=100));
+        assert_eq!(Some(50), interval.midpoint());
+
+        interval.narrow(50, Ordering::Less);
+        interval.narrow(80, Ordering::Greater);
+        assert_eq!((51, 79), (interval.low(), interval.high()));
+        assert_eq!(29, interval.len());
+        assert_eq!("the number is between 51 and 79", interval.to_string());
+
+        interval.narrow(60, Ordering::Equal);
+        assert_eq!("the number must be 60", interval.to_string());
+    }
+
+    #[test]
+    fn contradicting_answers_empty_the_interval() {
+        let mut interval = Interval::new(&(1..=10));
+        interval.narrow(5, Ordering::Less);
+        interval.narrow(6, Ordering::Greater);
+        assert!(interval.is_empty());
+        assert_eq!(None, interval.midpoint());
+
+        let mut interval = Interval::new(&(0..=10));
+        interval.narrow(0, Ordering::Greater);
+        assert!(interval.is_empty());
+
+        let mut interval = Interval::new(&(1..=10));
+        interval.narrow(3, Ordering::Greater);
+        interval.narrow(7, Ordering::Equal);
+        assert!(interval.is_empty());
+    }
+
+    #[test]
+    fn bot_finds_every_secret_within_the_attempts() {
+        for difficulty in [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard] {
+            for secret_number in difficulty.range() {
+                let outcome = auto_play(secret_number, &difficulty, &mut io::sink()).unwrap();
+                assert!(
+                    matches!(outcome, Outcome::Won { attempts } if attempts <= difficulty.max_attempts()),
+                    "{difficulty}: lost on {secret_number}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn bot_can_run_out_of_attempts() {
+        let difficulty = Difficulty::custom(1, 100, 2).unwrap();
+        let mut output = Vec::new();
+        let outcome = auto_play(1, &difficulty, &mut output).unwrap();
+
+        assert_eq!(Outcome::Lost, outcome);
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(
+            "Bot guesses: 50\nToo big!\nBot guesses: 25\nToo big!\n\
+             The bot ran out of attempts! The secret number was 1.\n",
+            output
+        );
+    }
+}
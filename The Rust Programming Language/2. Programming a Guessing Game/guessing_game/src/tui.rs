@@ -0,0 +1,386 @@
+//! A full-screen front end for one game: guess history, a bar showing the
+//! numbers still possible and a countdown of the attempts left.
+
+use std::cmp::Ordering;
+use std::io::{self, Write};
+use std::ops::RangeInclusive;
+
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::{cursor, execute, terminal};
+
+use crate::difficulty::Difficulty;
+use crate::game::Game;
+use crate::solver::Interval;
+use crate::Outcome;
+
+const GREEN: &str = "\x1B[32m";
+const RED: &str = "\x1B[31m";
+const RESET: &str = "\x1B[0m";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    /// The player has seen the result screen and pressed a key.
+    Done(Outcome),
+    Quit,
+}
+
+pub struct Screen {
+    game: Game,
+    input: String,
+    message: String,
+}
+
+impl Screen {
+    pub fn new(game: Game) -> Screen {
+        Screen {
+            game,
+            input: String::new(),
+            message: String::new(),
+        }
+    }
+
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Types, edits or submits a guess. Once the game is over any key leaves
+    /// the result screen; Esc or Ctrl-C quits at any point before that.
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<Action> {
+        if key.kind != KeyEventKind::Press {
+            return None;
+        }
+        if let Some(outcome) = self.game.outcome() {
+            return Some(Action::Done(outcome));
+        }
+
+        let control = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Esc => return Some(Action::Quit),
+            KeyCode::Char('c') if control => return Some(Action::Quit),
+            KeyCode::Char(c) if !control => self.input.push(c),
+            KeyCode::Backspace => {
+                self.input.pop();
+            }
+            KeyCode::Enter => {
+                self.message = match self.game.guess(&self.input) {
+                    Ok((guess, Ordering::Less)) => format!("{guess} is too small."),
+                    Ok((guess, Ordering::Greater)) => format!("{guess} is too big."),
+                    Ok((_, Ordering::Equal)) => String::new(),
+                    Err(rejected) => rejected.to_string(),
+                };
+                self.input.clear();
+            }
+            _ => {}
+        }
+
+        None
+    }
+
+    /// Draws the game, or the result screen once it is over, into a
+    /// `width` x `height` screen. Rows longer than `width` are cut short.
+    pub fn render(&self, out: &mut impl Write, width: usize, height: usize) -> io::Result<()> {
+        let rows = match self.game.outcome() {
+            Some(outcome) => self.result_rows(outcome),
+            None => self.game_rows(width, height),
+        };
+
+        write!(out, "\x1B[2J\x1B[H")?;
+        for (index, (color, text)) in rows.iter().enumerate() {
+            if index > 0 {
+                write!(out, "\r\n")?;
+            }
+            let text: String = text.chars().take(width).collect();
+            match color {
+                Some(color) => write!(out, "{color}{text}{RESET}")?,
+                None => write!(out, "{text}")?,
+            }
+        }
+        out.flush()
+    }
+
+    /// The game screen, top to bottom, ending with the input line.
+    fn game_rows(&self, width: usize, height: usize) -> Vec<Row> {
+        let range = self.game.range();
+        let interval = self.game.interval();
+        let mut rows = vec![
+            plain(format!(
+                "Guess a number between {} and {}",
+                range.start(),
+                range.end()
+            )),
+            plain(self.countdown(width)),
+            plain(String::new()),
+            plain(format!(
+                "[{}]",
+                range_bar(range, interval, width.saturating_sub(2))
+            )),
+            plain(format!("Possible: {}-{}", interval.low(), interval.high())),
+            plain("-".repeat(width)),
+            plain(String::from("History:")),
+        ];
+
+        // The newest guesses go at the top, and the list is padded so the
+        // message and input stay at the bottom of the screen.
+        let history = self.game.history();
+        let history_rows = height.saturating_sub(9);
+        let padded = rows.len() + history_rows;
+        let newest = history.iter().enumerate().rev().take(history_rows);
+        for (index, &(guess, answer)) in newest {
+            let answer = match answer {
+                Ordering::Less => "too small",
+                Ordering::Greater => "too big",
+                Ordering::Equal => "correct",
+            };
+            rows.push(plain(format!("{:>3}. {guess:>10}  {answer}", index + 1)));
+        }
+        rows.resize(padded, plain(String::new()));
+
+        rows.push(plain(self.message.clone()));
+        rows.push(plain(format!("> {}", self.input)));
+        rows
+    }
+
+    /// `Attempts left: 3   ●●●○○`, with a full circle per attempt left and an
+    /// empty one per attempt used. When that many circles wouldn't fit in
+    /// `width`, the total is written out instead.
+    fn countdown(&self, width: usize) -> String {
+        let left = self.game.attempts_left();
+        let used = self.game.attempts();
+        let label = format!("Attempts left: {left:<3} ");
+
+        let circles = u64::from(left) + u64::from(used);
+        let room = width.saturating_sub(label.chars().count());
+        if circles <= room as u64 {
+            format!(
+                "{label}{}{}",
+                "●".repeat(left as usize),
+                "○".repeat(used as usize)
+            )
+        } else {
+            format!("Attempts left: {left} of {circles}")
+        }
+    }
+
+    fn result_rows(&self, outcome: Outcome) -> Vec<Row> {
+        let secret_number = self.game.secret_number();
+        let result = match outcome {
+            Outcome::Won { attempts } => (
+                Some(GREEN),
+                format!("You win! You found {secret_number} in {attempts} guesses."),
+            ),
+            Outcome::Lost => (
+                Some(RED),
+                format!("You lose! The secret number was {secret_number}."),
+            ),
+        };
+
+        vec![
+            result,
+            plain(String::new()),
+            plain(String::from("Press any key to continue.")),
+        ]
+    }
+}
+
+/// A row of the screen and the color to draw it in.
+type Row = (Option<&'static str>, String);
+
+fn plain(text: String) -> Row {
+    (None, text)
+}
+
+/// Draws `low..=high` as `width` cells, filled where a cell still holds
+/// numbers inside `interval`.
+pub fn range_bar(range: &RangeInclusive<u32>, interval: &Interval, width: usize) -> String {
+    let low = u64::from(*range.start());
+    let span = u64::from(*range.end()) - low + 1;
+    let cells = width as u64;
+
+    (0..cells)
+        .map(|cell| {
+            let first = low + cell * span / cells;
+            let last = (low + (cell + 1) * span / cells).max(first + 1) - 1;
+            let open = !interval.is_empty()
+                && first <= u64::from(interval.high())
+                && last >= u64::from(interval.low());
+            if open {
+                '█'
+            } else {
+                '░'
+            }
+        })
+        .collect()
+}
+
+/// The real terminal, in raw mode and on the alternate screen until it is
+/// dropped. Dropping it is what puts the player's shell back, so that also
+/// happens when `play` returns early with an error.
+struct Terminal {
+    out: io::Stdout,
+}
+
+impl Terminal {
+    fn open() -> io::Result<Terminal> {
+        let mut out = io::stdout();
+        terminal::enable_raw_mode()?;
+        execute!(out, terminal::EnterAlternateScreen)?;
+        Ok(Terminal { out })
+    }
+
+    fn draw(&mut self, screen: &Screen) -> io::Result<()> {
+        let (width, height) = terminal::size()?;
+        screen.render(&mut self.out, usize::from(width), usize::from(height))
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        let _ = execute!(self.out, cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// Plays one game full screen. Returns `None` if the player quits early.
+pub fn play(secret_number: u32, difficulty: &Difficulty) -> io::Result<Option<Outcome>> {
+    let mut screen = Screen::new(Game::new(secret_number, difficulty));
+    let mut terminal = Terminal::open()?;
+
+    loop {
+        terminal.draw(&screen)?;
+
+        if let Event::Key(key) = event::read()? {
+            match screen.handle_key(key) {
+                Some(Action::Done(outcome)) => return Ok(Some(outcome)),
+                Some(Action::Quit) => return Ok(None),
+                None => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn type_guess(screen: &mut Screen, guess: &str) {
+        for c in guess.chars() {
+            assert_eq!(None, screen.handle_key(press(KeyCode::Char(c))));
+        }
+        assert_eq!(None, screen.handle_key(press(KeyCode::Enter)));
+    }
+
+    fn rendered(screen: &Screen, width: usize, height: usize) -> String {
+        let mut out = Vec::new();
+        screen.render(&mut out, width, height).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn typing_and_guessing() {
+        let mut screen = Screen::new(Game::new(7, &Difficulty::Easy));
+        screen.handle_key(press(KeyCode::Char('4')));
+        screen.handle_key(press(KeyCode::Char('x')));
+        screen.handle_key(press(KeyCode::Backspace));
+        assert_eq!("4", screen.input());
+
+        screen.handle_key(press(KeyCode::Enter));
+        assert_eq!("", screen.input());
+        assert_eq!("4 is too small.", screen.message());
+
+        type_guess(&mut screen, "4");
+        assert_eq!(
+            "You already guessed 4, try another number.",
+            screen.message()
+        );
+        type_guess(&mut screen, "99");
+        assert_eq!(
+            "99 is out of range, guess between 1 and 10.",
+            screen.message()
+        );
+    }
+
+    #[test]
+    fn bar_narrows_with_answers() {
+        let mut interval = Interval::new(&(1..=100));
+        assert_eq!("██████████", range_bar(&(1..=100), &interval, 10));
+
+        interval.narrow(50, Ordering::Less);
+        assert_eq!("░░░░░█████", range_bar(&(1..=100), &interval, 10));
+
+        interval.narrow(62, Ordering::Greater);
+        assert_eq!("░░░░░██░░░", range_bar(&(1..=100), &interval, 10));
+
+        let mut interval = Interval::new(&(1..=3));
+        interval.narrow(1, Ordering::Less);
+        assert_eq!("░░████", range_bar(&(1..=3), &interval, 6));
+    }
+
+    #[test]
+    fn render_shows_history_and_countdown() {
+        let mut screen = Screen::new(Game::new(7, &Difficulty::Easy));
+        type_guess(&mut screen, "3");
+        type_guess(&mut screen, "9");
+        screen.handle_key(press(KeyCode::Char('8')));
+
+        let screen = rendered(&screen, 40, 14);
+        assert!(screen.contains("Guess a number between 1 and 10\r\n"));
+        assert!(screen.contains("Attempts left: 3   ●●●○○\r\n"));
+        assert!(screen.contains("[░░░░░░░░░░░░███████████████████░░░░░░░]\r\n"));
+        assert!(screen.contains("Possible: 4-8\r\n"));
+        assert!(screen.contains("  2.          9  too big\r\n  1.          3  too small\r\n"));
+        assert!(screen.contains("9 is too big.\r\n"));
+        assert!(screen.ends_with("> 8"));
+    }
+
+    #[test]
+    fn result_screens_are_colored() {
+        let mut screen = Screen::new(Game::new(7, &Difficulty::Easy));
+        type_guess(&mut screen, "7");
+        assert!(
+            rendered(&screen, 60, 10).contains("\x1B[32mYou win! You found 7 in 1 guesses.\x1B[0m")
+        );
+        assert_eq!(
+            Some(Action::Done(Outcome::Won { attempts: 1 })),
+            screen.handle_key(press(KeyCode::Char(' ')))
+        );
+
+        let difficulty = Difficulty::custom(1, 10, 1).unwrap();
+        let mut screen = Screen::new(Game::new(7, &difficulty));
+        type_guess(&mut screen, "2");
+        assert!(
+            rendered(&screen, 60, 10).contains("\x1B[31mYou lose! The secret number was 7.\x1B[0m")
+        );
+        assert_eq!(
+            Some(Action::Done(Outcome::Lost)),
+            screen.handle_key(press(KeyCode::Esc))
+        );
+    }
+
+    #[test]
+    fn escape_quits() {
+        let mut screen = Screen::new(Game::new(7, &Difficulty::Easy));
+        assert_eq!(Some(Action::Quit), screen.handle_key(press(KeyCode::Esc)));
+
+        let control_c = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL);
+        assert_eq!(Some(Action::Quit), screen.handle_key(control_c));
+    }
+
+    #[test]
+    fn countdown_falls_back_to_a_number() {
+        let difficulty = Difficulty::custom(1, 100, 10_000_000).unwrap();
+        let mut screen = Screen::new(Game::new(7, &difficulty));
+        type_guess(&mut screen, "3");
+
+        let screen = rendered(&screen, 40, 14);
+        assert!(screen.contains("Attempts left: 9999999 of 10000000\r\n"));
+        assert!(!screen.contains('●'));
+    }
+}
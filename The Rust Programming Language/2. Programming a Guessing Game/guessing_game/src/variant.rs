@@ -0,0 +1,186 @@
+//! Other guessing games that share the number game's session statistics,
+//! scoring, leaderboard and seeded RNG.
+
+use std::collections::HashSet;
+use std::io::{self, BufRead, Write};
+
+use rand::Rng;
+
+use crate::bulls_and_cows::BullsAndCows;
+use crate::read_line;
+use crate::score::Puzzle;
+use crate::word_game::WordGame;
+use crate::Outcome;
+
+/// The answer to one counted guess.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Clue {
+    pub text: String,
+    pub solved: bool,
+}
+
+/// A game where the player guesses a hidden secret and gets a clue back
+/// after each try.
+pub trait Variant {
+    /// Explains the game before the first guess.
+    fn rules(&self) -> String;
+
+    fn puzzle(&self) -> Puzzle;
+
+    /// Judges a guess, or explains why it can't be counted.
+    fn check(&self, guess: &str) -> Result<Clue, String>;
+
+    /// The secret, revealed when the player runs out of attempts.
+    fn secret(&self) -> String;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VariantKind {
+    BullsAndCows,
+    Words,
+}
+
+impl VariantKind {
+    pub fn parse(name: &str) -> Option<VariantKind> {
+        match name {
+            "bulls" => Some(VariantKind::BullsAndCows),
+            "words" => Some(VariantKind::Words),
+            _ => None,
+        }
+    }
+
+    /// Picks a new secret for this kind of game.
+    pub fn start(self, rng: &mut impl Rng) -> Box<dyn Variant> {
+        match self {
+            VariantKind::BullsAndCows => Box::new(BullsAndCows::new(rng)),
+            VariantKind::Words => Box::new(WordGame::new(rng)),
+        }
+    }
+}
+
+/// Plays one game of `variant`. Invalid and repeated guesses are explained
+/// and don't use up an attempt. Returns `None` if the input is closed
+/// mid-game.
+pub fn play(
+    variant: &dyn Variant,
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+) -> io::Result<Option<Outcome>> {
+    let max_attempts = variant.puzzle().max_attempts;
+    writeln!(output, "{}", variant.rules())?;
+    writeln!(output, "You have {max_attempts} attempts.")?;
+
+    let mut attempts = 0;
+    let mut guessed = HashSet::new();
+
+    loop {
+        let Some(line) = read_line(input)? else {
+            return Ok(None);
+        };
+
+        let clue = match variant.check(&line) {
+            Ok(clue) => clue,
+            Err(reason) => {
+                writeln!(output, "{reason}")?;
+                continue;
+            }
+        };
+
+        let guess = line.trim().to_lowercase();
+        if !guessed.insert(guess.clone()) {
+            writeln!(output, "You already guessed {guess}, try another.")?;
+            continue;
+        }
+
+        attempts += 1;
+        writeln!(output, "{}", clue.text)?;
+
+        if clue.solved {
+            writeln!(output, "You win!")?;
+            return Ok(Some(Outcome::Won { attempts }));
+        }
+
+        if attempts == max_attempts {
+            writeln!(output, "You lose! The secret was {}.", variant.secret())?;
+            return Ok(Some(Outcome::Lost));
+        }
+
+        writeln!(output, "{} attempts left.", max_attempts - attempts)?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cmp::Ordering;
+
+    /// Guess the hidden letter; the clue says which way to go.
+    struct Letter(char);
+
+    impl Variant for Letter {
+        fn rules(&self) -> String {
+            String::from("Guess the letter.")
+        }
+
+        fn puzzle(&self) -> Puzzle {
+            Puzzle {
+                name: "Letter",
+                possibilities: 26,
+                max_attempts: 3,
+            }
+        }
+
+        fn check(&self, guess: &str) -> Result<Clue, String> {
+            let mut chars = guess.trim().chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) if c.is_ascii_lowercase() => Ok(Clue {
+                    text: String::from(match c.cmp(&self.0) {
+                        Ordering::Less => "later",
+                        Ordering::Greater => "earlier",
+                        Ordering::Equal => "yes",
+                    }),
+                    solved: c == self.0,
+                }),
+                _ => Err(String::from("One lowercase letter, please.")),
+            }
+        }
+
+        fn secret(&self) -> String {
+            self.0.to_string()
+        }
+    }
+
+    fn play_with(variant: &dyn Variant, input: &str) -> (Option<Outcome>, String) {
+        let mut output = Vec::new();
+        let outcome = play(variant, &mut input.as_bytes(), &mut output).unwrap();
+        (outcome, String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn parse_kinds() {
+        assert_eq!(Some(VariantKind::BullsAndCows), VariantKind::parse("bulls"));
+        assert_eq!(Some(VariantKind::Words), VariantKind::parse("words"));
+        assert_eq!(None, VariantKind::parse("chess"));
+    }
+
+    #[test]
+    fn win_with_rejected_and_repeated_guesses() {
+        let (outcome, output) = play_with(&Letter('m'), "a\nAB\na\nm\n");
+
+        assert_eq!(Some(Outcome::Won { attempts: 2 }), outcome);
+        assert!(output.starts_with("Guess the letter.\nYou have 3 attempts.\n"));
+        assert!(output.contains("later\n2 attempts left.\n"));
+        assert!(output.contains("One lowercase letter, please.\n"));
+        assert!(output.contains("You already guessed a, try another.\n"));
+        assert!(output.ends_with("yes\nYou win!\n"));
+    }
+
+    #[test]
+    fn lose_reveals_the_secret() {
+        let (outcome, output) = play_with(&Letter('z'), "a\nb\nc\n");
+
+        assert_eq!(Some(Outcome::Lost), outcome);
+        assert!(output.ends_with("You lose! The secret was z.\n"));
+        assert_eq!(None, play_with(&Letter('z'), "a\n").0);
+    }
+}
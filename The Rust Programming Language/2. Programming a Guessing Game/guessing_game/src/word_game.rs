@@ -0,0 +1,171 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::score::Puzzle;
+use crate::variant::{Clue, Variant};
+
+const WORDS: &str = include_str!("words.txt");
+const LETTERS: usize = 5;
+const MAX_ATTEMPTS: u32 = 6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mark {
+    /// The letter is in this place.
+    Right,
+    /// The letter is in the word, somewhere else.
+    Elsewhere,
+    Absent,
+}
+
+/// Guess a five-letter word from the bundled list. Each letter of a guess is
+/// marked as in the right place, elsewhere in the word, or absent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WordGame {
+    secret: &'static str,
+}
+
+impl WordGame {
+    pub fn new(rng: &mut impl Rng) -> WordGame {
+        let words: Vec<&'static str> = words().collect();
+        WordGame {
+            secret: words.choose(rng).expect("the word list is not empty"),
+        }
+    }
+
+    /// Marks each letter of `guess`. A letter guessed more often than it
+    /// appears in the secret is only marked as many times as it appears,
+    /// right places first.
+    pub fn marks(&self, guess: &str) -> [Mark; LETTERS] {
+        let secret = self.secret.as_bytes();
+        let guess = guess.as_bytes();
+        let mut marks = [Mark::Absent; LETTERS];
+        let mut unmatched = [0; 26];
+
+        for i in 0..LETTERS {
+            if guess[i] == secret[i] {
+                marks[i] = Mark::Right;
+            } else {
+                unmatched[usize::from(secret[i] - b'a')] += 1;
+            }
+        }
+
+        for i in 0..LETTERS {
+            let letter = usize::from(guess[i] - b'a');
+            if marks[i] != Mark::Right && unmatched[letter] > 0 {
+                unmatched[letter] -= 1;
+                marks[i] = Mark::Elsewhere;
+            }
+        }
+
+        marks
+    }
+}
+
+fn words() -> impl Iterator<Item = &'static str> {
+    WORDS.lines().filter(|word| !word.is_empty())
+}
+
+impl Variant for WordGame {
+    fn rules(&self) -> String {
+        format!(
+            "Guess the {LETTERS}-letter word. Letters in the right place are shown as [a],\n\
+             letters elsewhere in the word as (a)."
+        )
+    }
+
+    fn puzzle(&self) -> Puzzle {
+        Puzzle {
+            name: "Words",
+            possibilities: words().count() as u64,
+            max_attempts: MAX_ATTEMPTS,
+        }
+    }
+
+    fn check(&self, guess: &str) -> Result<Clue, String> {
+        let guess = guess.trim().to_lowercase();
+        if guess.chars().count() != LETTERS || !guess.chars().all(|c| c.is_ascii_lowercase()) {
+            return Err(format!("Please type a {LETTERS}-letter word."));
+        }
+        if !words().any(|word| word == guess) {
+            return Err(format!("'{guess}' is not in the word list."));
+        }
+
+        let marks = self.marks(&guess);
+        let text = guess
+            .chars()
+            .zip(marks)
+            .map(|(letter, mark)| match mark {
+                Mark::Right => format!("[{letter}]"),
+                Mark::Elsewhere => format!("({letter})"),
+                Mark::Absent => format!(" {letter} "),
+            })
+            .collect();
+
+        Ok(Clue {
+            text,
+            solved: marks == [Mark::Right; LETTERS],
+        })
+    }
+
+    fn secret(&self) -> String {
+        self.secret.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::make_rng;
+    use Mark::*;
+
+    #[test]
+    fn word_list() {
+        assert!(words().count() > 100);
+        assert!(words()
+            .all(|word| word.len() == LETTERS
+                && word.bytes().all(|letter| letter.is_ascii_lowercase())));
+
+        let game = WordGame::new(&mut make_rng(Some(3)));
+        assert!(words().any(|word| word == game.secret));
+    }
+
+    #[test]
+    fn marks() {
+        let game = WordGame { secret: "crane" };
+        assert_eq!([Right; 5], game.marks("crane"));
+        assert_eq!(
+            [Absent, Elsewhere, Absent, Elsewhere, Elsewhere],
+            game.marks("under")
+        );
+        // Only one of the two e's in "sleep" can be the e in "crane".
+        assert_eq!(
+            [Absent, Absent, Elsewhere, Absent, Absent],
+            game.marks("sleep")
+        );
+
+        // The right place is marked first, so the other e is left over.
+        let game = WordGame { secret: "sleep" };
+        assert_eq!(
+            [Absent, Absent, Right, Elsewhere, Elsewhere],
+            game.marks("these")
+        );
+    }
+
+    #[test]
+    fn clues() {
+        let game = WordGame { secret: "sleep" };
+        let clue = game.check("Steel\n").unwrap();
+        assert_eq!("[s] t [e][e](l)", clue.text);
+        assert!(!clue.solved);
+        assert!(game.check("sleep").unwrap().solved);
+
+        assert_eq!(
+            Err(String::from("Please type a 5-letter word.")),
+            game.check("cat")
+        );
+        assert_eq!(
+            Err(String::from("'zzzzz' is not in the word list.")),
+            game.check("zzzzz")
+        );
+    }
+}
@@ -0,0 +1,304 @@
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::Duration;
+
+use guessing_game::difficulty::Difficulty;
+use guessing_game::network::{self, Mode, PlayerResult, ServerMessage};
+use guessing_game::Outcome;
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+fn listener() -> (TcpListener, String) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    (listener, address)
+}
+
+fn join(address: &str, input: &str) -> (Option<Outcome>, String) {
+    let mut output = Vec::new();
+    let outcome = network::join(address, TIMEOUT, &mut input.as_bytes(), &mut output).unwrap();
+    (outcome, String::from_utf8(output).unwrap())
+}
+
+#[test]
+fn pick_game() {
+    let (listener, address) = listener();
+
+    let server = thread::spawn(move || {
+        let mut log = Vec::new();
+        let results = network::serve(
+            &listener,
+            Mode::Pick,
+            42,
+            &Difficulty::Normal,
+            TIMEOUT,
+            &mut log,
+        )
+        .unwrap();
+        (results, String::from_utf8(log).unwrap())
+    });
+
+    let (outcome, output) = join(&address, "50\nfifty\n25\n25\n42\n");
+    let (results, log) = server.join().unwrap();
+
+    assert_eq!(Some(Outcome::Won { attempts: 3 }), outcome);
+    assert!(output.starts_with("The host picked a number between 1 and 100.\n"));
+    assert!(output.contains("Too big!\n6 attempts left.\n"));
+    assert!(output.contains("'fifty' is not a whole number.\n"));
+    assert!(output.contains("You already guessed 25, try another number.\n"));
+    assert!(output.ends_with("You win!\n"));
+
+    assert_eq!(vec![PlayerResult::Won { attempts: 3 }], results);
+    assert!(log.ends_with("Player 1 guessed 42.\nPlayer 1 wins!\n"));
+}
+
+#[test]
+fn race_game() {
+    let (listener, address) = listener();
+
+    let server = thread::spawn(move || {
+        network::serve(
+            &listener,
+            Mode::Race,
+            7,
+            &Difficulty::Easy,
+            TIMEOUT,
+            Vec::new(),
+        )
+        .unwrap()
+    });
+
+    let loser = {
+        let address = address.clone();
+        thread::spawn(move || join(&address, "1\n2\n3\n4\n5\n"))
+    };
+    let (winner_outcome, winner_output) = join(&address, "7\n");
+    let (loser_outcome, loser_output) = loser.join().unwrap();
+    let results = server.join().unwrap();
+
+    assert_eq!(Some(Outcome::Won { attempts: 1 }), winner_outcome);
+    assert!(winner_output.starts_with("Race the other player"));
+    assert_eq!(Some(Outcome::Lost), loser_outcome);
+    assert!(loser_output.ends_with("You lose! The secret number was 7.\n"));
+    assert_eq!(2, results.len());
+    assert!(results.contains(&PlayerResult::Won { attempts: 1 }));
+    assert!(results.contains(&PlayerResult::Lost));
+}
+
+/// A host log that hands each write to the test as it happens, so a test can
+/// wait for the server to reach a point instead of sleeping.
+struct LogChannel(Sender<String>);
+
+impl Write for LogChannel {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // The test may have stopped listening; the server doesn't care.
+        let _ = self.0.send(String::from_utf8_lossy(buf).into_owned());
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Reads the log until it contains `text`, and returns everything read.
+fn wait_for(log: &Receiver<String>, text: &str) -> String {
+    let mut seen = String::new();
+    while !seen.contains(text) {
+        seen.push_str(&log.recv().unwrap());
+    }
+    seen
+}
+
+fn raw_client(address: &str) -> (TcpStream, BufReader<TcpStream>) {
+    let stream = TcpStream::connect(address).unwrap();
+    let reader = BufReader::new(stream.try_clone().unwrap());
+    (stream, reader)
+}
+
+fn next_message(reader: &mut impl BufRead) -> Option<ServerMessage> {
+    let mut line = String::new();
+    reader.read_line(&mut line).unwrap();
+    ServerMessage::parse(&line)
+}
+
+#[test]
+fn idle_player_times_out() {
+    let (listener, address) = listener();
+    let timeout = Duration::from_millis(200);
+
+    let server = thread::spawn(move || {
+        network::serve(
+            &listener,
+            Mode::Pick,
+            3,
+            &Difficulty::Easy,
+            timeout,
+            Vec::new(),
+        )
+        .unwrap()
+    });
+
+    let (_stream, mut reader) = raw_client(&address);
+    assert!(matches!(
+        next_message(&mut reader),
+        Some(ServerMessage::Hello { .. })
+    ));
+    assert_eq!(Some(ServerMessage::Start), next_message(&mut reader));
+    assert_eq!(
+        Some(ServerMessage::End(String::from("timed out"))),
+        next_message(&mut reader)
+    );
+
+    assert_eq!(vec![PlayerResult::TimedOut], server.join().unwrap());
+}
+
+#[test]
+fn disconnect_is_reported() {
+    let (listener, address) = listener();
+
+    let server = thread::spawn(move || {
+        network::serve(
+            &listener,
+            Mode::Pick,
+            3,
+            &Difficulty::Easy,
+            TIMEOUT,
+            Vec::new(),
+        )
+        .unwrap()
+    });
+
+    let (mut stream, mut reader) = raw_client(&address);
+    next_message(&mut reader);
+    writeln!(stream, "GUESS 5").unwrap();
+    stream.shutdown(std::net::Shutdown::Write).unwrap();
+
+    assert_eq!(vec![PlayerResult::Disconnected], server.join().unwrap());
+}
+
+#[test]
+fn closed_input_quits() {
+    let (listener, address) = listener();
+
+    let server = thread::spawn(move || {
+        network::serve(
+            &listener,
+            Mode::Pick,
+            3,
+            &Difficulty::Easy,
+            TIMEOUT,
+            Vec::new(),
+        )
+        .unwrap()
+    });
+
+    let (outcome, _) = join(&address, "9\n");
+
+    assert_eq!(None, outcome);
+    assert_eq!(vec![PlayerResult::Quit], server.join().unwrap());
+}
+
+#[test]
+fn loser_is_told_at_once() {
+    let (listener, address) = listener();
+
+    let server = thread::spawn(move || {
+        network::serve(
+            &listener,
+            Mode::Race,
+            7,
+            &Difficulty::Easy,
+            TIMEOUT,
+            Vec::new(),
+        )
+        .unwrap()
+    });
+
+    let (_stream, mut reader) = raw_client(&address);
+    next_message(&mut reader);
+    assert_eq!(Some(ServerMessage::Wait), next_message(&mut reader));
+
+    let (outcome, _) = join(&address, "7\n");
+    assert_eq!(Some(Outcome::Won { attempts: 1 }), outcome);
+
+    assert_eq!(Some(ServerMessage::Start), next_message(&mut reader));
+    assert_eq!(
+        Some(ServerMessage::Lose { secret: 7 }),
+        next_message(&mut reader)
+    );
+    assert_eq!(
+        vec![PlayerResult::Lost, PlayerResult::Won { attempts: 1 }],
+        server.join().unwrap()
+    );
+}
+
+#[test]
+fn race_is_called_off_without_an_opponent() {
+    let (listener, address) = listener();
+    let timeout = Duration::from_secs(2);
+    let (sender, log) = mpsc::channel();
+
+    let server = thread::spawn(move || {
+        network::serve(
+            &listener,
+            Mode::Race,
+            7,
+            &Difficulty::Easy,
+            timeout,
+            LogChannel(sender),
+        )
+        .unwrap()
+    });
+
+    // This player gives up before anyone else joins.
+    let (quitter, mut reader) = raw_client(&address);
+    next_message(&mut reader);
+    assert_eq!(Some(ServerMessage::Wait), next_message(&mut reader));
+    drop((quitter, reader));
+    wait_for(&log, "A waiting player left, 0 still waiting.\n");
+
+    let (_stream, mut reader) = raw_client(&address);
+    next_message(&mut reader);
+    assert_eq!(Some(ServerMessage::Wait), next_message(&mut reader));
+    assert_eq!(
+        Some(ServerMessage::End(String::from("no one else joined"))),
+        next_message(&mut reader)
+    );
+
+    assert_eq!(vec![PlayerResult::TimedOut], server.join().unwrap());
+    let rest: String = log.iter().collect();
+    assert!(rest.ends_with("Not enough players joined in time.\n"));
+}
+
+#[test]
+fn stalled_server_ends_the_game() {
+    let (listener, address) = listener();
+
+    // A server that starts the game and then never answers.
+    let server = thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        writeln!(stream, "HELLO pick 1 100 7").unwrap();
+        writeln!(stream, "START").unwrap();
+        let mut guess = String::new();
+        BufReader::new(&stream).read_line(&mut guess).unwrap();
+        (stream, guess)
+    });
+
+    let mut output = Vec::new();
+    let outcome = network::join(
+        &address,
+        Duration::from_millis(100),
+        &mut "50\n".as_bytes(),
+        &mut output,
+    )
+    .unwrap();
+    let (_stream, guess) = server.join().unwrap();
+
+    assert_eq!(None, outcome);
+    assert_eq!("GUESS 50\n", guess);
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.ends_with("Game over: the server stopped responding.\n"));
+}
@@ -0,0 +1,209 @@
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::ops::RangeInclusive;
+use std::process::{Command, Output, Stdio};
+
+use guessing_game::bulls_and_cows::BullsAndCows;
+use guessing_game::difficulty::Difficulty;
+use guessing_game::variant::Variant;
+use guessing_game::{generate_secret_number, make_rng};
+
+fn play(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_guessing_game"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to start guessing_game");
+
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+/// `count` different guesses in `range` that all miss `secret`.
+fn wrong_guesses(secret: u32, range: RangeInclusive<u32>, count: usize) -> String {
+    range
+        .filter(|&guess| guess != secret)
+        .take(count)
+        .map(|guess| format!("{guess}\n"))
+        .collect()
+}
+
+fn secret(seed: u64, difficulty: &Difficulty) -> u32 {
+    generate_secret_number(&mut make_rng(Some(seed)), difficulty)
+}
+
+#[test]
+fn seeded_game_is_won_with_known_secret() {
+    let secret = secret(42, &Difficulty::Normal);
+    let guesses = format!("{}\n{secret}\n", if secret == 1 { 2 } else { 1 });
+
+    let output = play(&["--seed", "42", "--difficulty", "normal"], &guesses);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(output.status.success());
+    assert!(stdout.contains(&format!("You guessed: {secret}\nYou win!")));
+}
+
+#[test]
+fn seeded_game_is_lost_and_reveals_secret() {
+    let secret = secret(3, &Difficulty::Hard);
+    let guesses = wrong_guesses(secret, Difficulty::Hard.range(), 10);
+
+    let output = play(&["--seed", "3", "--difficulty", "hard"], &guesses);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(10, stdout.matches("You guessed:").count());
+    assert!(stdout.contains(&format!("You lose! The secret number was {secret}.\n")));
+}
+
+#[test]
+fn same_seed_replays_the_same_session() {
+    let guesses = "50\n25\n75\n12\n88\n3\n";
+    let args = ["--seed", "1234", "--difficulty", "normal"];
+
+    let first = play(&args, guesses);
+    let second = play(&args, guesses);
+
+    assert_eq!(first.stdout, second.stdout);
+}
+
+#[test]
+fn start_menu_through_stdin() {
+    let secret = secret(9, &Difficulty::Easy);
+    let output = play(&["--seed", "9"], &format!("1\n{secret}\n"));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(stdout.contains("Guess a number between 1 and 10. You have 5 attempts."));
+    assert!(stdout.contains("You win!\nYour score: "));
+}
+
+#[test]
+fn winner_joins_the_leaderboard() {
+    let dir = env::temp_dir().join(format!("seeded_games-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let file = dir.join("leaderboard.txt");
+    let file = file.to_str().unwrap();
+
+    let secret = secret(5, &Difficulty::Easy);
+    let args = [
+        "--seed",
+        "5",
+        "--difficulty",
+        "easy",
+        "--leaderboard-file",
+        file,
+    ];
+    let output = play(&args, &format!("{secret}\nSally\n"));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout.contains("Enter your name for the leaderboard: You made the leaderboard at #1!\n")
+    );
+
+    let output = play(&["--leaderboard", "--leaderboard-file", file], "");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert!(stdout.contains("Sally"));
+    assert!(stdout.contains("Easy"));
+}
+
+#[test]
+fn several_rounds_with_statistics() {
+    let dir = env::temp_dir().join(format!("seeded_rounds-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let csv = dir.join("stats.csv");
+
+    let mut rng = make_rng(Some(11));
+    let first = generate_secret_number(&mut rng, &Difficulty::Easy);
+    let second = generate_secret_number(&mut rng, &Difficulty::Easy);
+    let wrong = wrong_guesses(second, Difficulty::Easy.range(), 5);
+
+    let stdin = format!("{first}\n\ny\n{wrong}n\n");
+    let args = ["--seed", "11", "--difficulty", "easy", "--csv"];
+    let output = play(&[&args[..], &[csv.to_str().unwrap()]].concat(), &stdin);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let csv = fs::read_to_string(&csv).unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(2, stdout.matches("Play again? (y/n)").count());
+    assert!(stdout.contains("Games played: 2\nWin rate: 50.0%\n"));
+    assert!(stdout.ends_with("Guesses to win:\n  1 | # (1)\n"));
+    assert!(csv.starts_with("statistic,value\ngames_played,2\nwins,1\n"));
+}
+
+#[test]
+fn closed_stdin_ends_cleanly() {
+    let output = play(&["--seed", "8", "--difficulty", "hard"], "500\nfive\n");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(output.status.success());
+    assert!(output.stderr.is_empty());
+    assert!(stdout.contains("'five' is not a whole number.\n"));
+    assert!(stdout.contains("Input closed, goodbye!\nSession statistics:\nGames played: 0\n"));
+}
+
+#[test]
+fn bot_plays_by_binary_search() {
+    let secret = secret(21, &Difficulty::Hard);
+    let output = play(&["--seed", "21", "--difficulty", "hard", "--auto"], "");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(output.status.success());
+    assert!(stdout.contains("Bot guesses: 500\n"));
+    assert!(stdout.contains(&format!("Bot guesses: {secret}\nThe bot needed ")));
+
+    let guesses = stdout.matches("Bot guesses:").count();
+    assert!(guesses <= 10);
+    assert!(stdout.ends_with(&format!("The bot needed {guesses} guesses.\n")));
+}
+
+#[test]
+fn reverse_game_catches_a_cheater() {
+    let output = play(
+        &["--reverse", "--difficulty", "normal"],
+        "higher\nlower\nhigher\nlower\nhigher\nlower\n",
+    );
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(output.status.success());
+    assert!(stdout.contains("Think of a number between 1 and 100, and I'll guess it.\n"));
+    assert_eq!(6, stdout.matches("Is it ").count());
+    assert!(stdout.ends_with("You're cheating! No number between 1 and 100 fits your answers.\n"));
+}
+
+#[test]
+fn tui_falls_back_to_plain_without_a_terminal() {
+    let secret = secret(4, &Difficulty::Easy);
+    let output = play(
+        &["--tui", "--seed", "4", "--difficulty", "easy"],
+        &format!("{secret}\n\nn\n"),
+    );
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(output.status.success());
+    assert!(!stdout.contains('\x1B'));
+    assert!(stdout.contains(&format!("You guessed: {secret}\nYou win!\n")));
+}
+
+#[test]
+fn bulls_and_cows_shares_the_session() {
+    let secret = BullsAndCows::new(&mut make_rng(Some(6))).secret();
+    let args = ["--game", "bulls", "--seed", "6"];
+    let output = play(&args, &format!("{secret}\n\nn\n"));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(output.status.success());
+    assert!(stdout.contains("Guess the 4 different digits."));
+    assert!(stdout.contains("4 bulls, 0 cows\nYou win!\nYour score: "));
+    assert!(stdout.contains("Games played: 1\nWin rate: 100.0%\n"));
+}
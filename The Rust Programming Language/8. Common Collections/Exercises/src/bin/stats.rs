@@ -0,0 +1,16 @@
+use std::env;
+use std::process;
+
+use exercises::stats_cli::{self, Config};
+
+fn main() {
+    let config = Config::build(env::args()).unwrap_or_else(|err| {
+        eprintln!("Problem parsing arguments: {err}");
+        process::exit(1);
+    });
+
+    if let Err(e) = stats_cli::run(config) {
+        eprintln!("Application error: {e}");
+        process::exit(1);
+    }
+}
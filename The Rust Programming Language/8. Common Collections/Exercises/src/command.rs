@@ -0,0 +1,466 @@
+//! The commands the company directory understands, such as "Add Sally to
+//! Engineering", and the parser that reads them.
+//!
+//! Keywords are matched case-insensitively. Names and departments may be
+//! several words long, or quoted when they contain a keyword themselves:
+//! `Add "Anne Marie To" to Sales`.
+
+use std::fmt;
+use std::ops::Range;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Add {
+        name: String,
+        department: String,
+    },
+    Remove {
+        name: String,
+        department: String,
+    },
+    Move {
+        name: String,
+        department: String,
+    },
+    List(String),
+    ListAll,
+    Rename {
+        from: String,
+        to: String,
+    },
+    /// Reads employees from a CSV file, only reporting what would change
+    /// when `dry_run`.
+    Import {
+        path: String,
+        dry_run: bool,
+    },
+    /// Writes the directory as CSV to a file, or to stdout without one.
+    Export(Option<String>),
+    Undo,
+    Redo,
+    /// Shows the audit log, or only the entries about one employee or
+    /// department.
+    History(Option<String>),
+    Help,
+    Quit,
+}
+
+const VERBS: &str =
+    "Add, Remove, Move, List, Rename, Import, Export, Undo, Redo, History, Help or Quit";
+
+pub const HELP: &str = "\
+Add <name> to <department>
+Remove <name> from <department>
+Move <name> to <department>
+List <department>
+List all
+Rename <department> to <department>
+Import <file.csv> [dry run]
+Export [<file.csv>]
+Undo
+Redo
+History [<name or department>]
+Help
+Quit";
+
+/// Why a command couldn't be parsed, and where in the input the problem is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub message: String,
+    /// The bytes of the input at fault, empty at the end of the input when
+    /// something is missing.
+    pub span: Range<usize>,
+}
+
+impl ParseError {
+    fn new(message: String, span: Range<usize>) -> ParseError {
+        ParseError { message, span }
+    }
+
+    /// The input with the offending part marked underneath it.
+    pub fn show(&self, input: &str) -> String {
+        let column = input[..self.span.start].chars().count();
+        let width = input[self.span.clone()].chars().count().max(1);
+        format!("{input}\n{}{}", " ".repeat(column), "^".repeat(width))
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// A word of the input and where it is. Quoted words keep their spaces.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Word<'a> {
+    text: &'a str,
+    quoted: bool,
+    span: Range<usize>,
+}
+
+impl Word<'_> {
+    fn is(&self, keyword: &str) -> bool {
+        !self.quoted && self.text.eq_ignore_ascii_case(keyword)
+    }
+}
+
+fn words(input: &str) -> Result<Vec<Word<'_>>, ParseError> {
+    let mut words = Vec::new();
+    let mut chars = input.char_indices().peekable();
+
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '"' {
+            chars.next();
+            let end = chars
+                .find(|&(_, c)| c == '"')
+                .map(|(end, _)| end)
+                .ok_or_else(|| {
+                    ParseError::new(String::from("this quote is never closed"), start..start + 1)
+                })?;
+            words.push(Word {
+                text: &input[start + 1..end],
+                quoted: true,
+                span: start..end + 1,
+            });
+        } else {
+            let mut end = input.len();
+            while let Some(&(i, c)) = chars.peek() {
+                if c.is_whitespace() || c == '"' {
+                    end = i;
+                    break;
+                }
+                chars.next();
+            }
+            words.push(Word {
+                text: &input[start..end],
+                quoted: false,
+                span: start..end,
+            });
+        }
+    }
+    Ok(words)
+}
+
+/// Parses one line of input.
+pub fn parse(input: &str) -> Result<Command, ParseError> {
+    let words = words(input)?;
+    let end = input.trim_end().len();
+    let Some((verb, rest)) = words.split_first() else {
+        return Err(ParseError::new(
+            format!("expected a command, one of {VERBS}"),
+            end..end,
+        ));
+    };
+
+    let verb_name = verb.text.to_ascii_lowercase();
+    let command = match verb_name.as_str() {
+        "add" | "move" | "remove" => {
+            let keyword = if verb_name == "remove" { "from" } else { "to" };
+            let (name, department) = split(verb, rest, keyword, "a name", "a department", end)?;
+            match verb_name.as_str() {
+                "add" => Command::Add { name, department },
+                "move" => Command::Move { name, department },
+                _ => Command::Remove { name, department },
+            }
+        }
+        "rename" => {
+            let (from, to) = split(verb, rest, "to", "a department", "a new name", end)?;
+            Command::Rename { from, to }
+        }
+        "list" => match rest {
+            [] => {
+                return Err(ParseError::new(
+                    format!("expected a department or 'all' after '{}'", verb.text),
+                    end..end,
+                ))
+            }
+            [all] if all.is("all") => Command::ListAll,
+            _ => Command::List(phrase(rest)),
+        },
+        "import" => {
+            let (path, dry_run) = match rest {
+                [path @ .., dry, run] if dry.is("dry") && run.is("run") => (path, true),
+                path => (path, false),
+            };
+            if path.is_empty() {
+                return Err(ParseError::new(
+                    format!("expected a file after '{}'", verb.text),
+                    rest.first().map_or(end..end, |word| word.span.clone()),
+                ));
+            }
+            Command::Import {
+                path: phrase(path),
+                dry_run,
+            }
+        }
+        "export" => match rest {
+            [] => Command::Export(None),
+            path => Command::Export(Some(phrase(path))),
+        },
+        "history" => match rest {
+            [] => Command::History(None),
+            name => Command::History(Some(phrase(name))),
+        },
+        "help" | "quit" | "undo" | "redo" => match rest {
+            [] => match verb_name.as_str() {
+                "help" => Command::Help,
+                "undo" => Command::Undo,
+                "redo" => Command::Redo,
+                _ => Command::Quit,
+            },
+            [extra, ..] => {
+                return Err(ParseError::new(
+                    format!("'{}' takes nothing after it", verb.text),
+                    extra.span.start..end,
+                ))
+            }
+        },
+        _ => {
+            return Err(ParseError::new(
+                format!("unknown command '{}', expected one of {VERBS}", verb.text),
+                verb.span.clone(),
+            ))
+        }
+    };
+    Ok(command)
+}
+
+/// Splits the words after `verb` on `keyword` into what comes before and
+/// after it, both of which must be there.
+fn split(
+    verb: &Word,
+    rest: &[Word],
+    keyword: &str,
+    before: &str,
+    after: &str,
+    end: usize,
+) -> Result<(String, String), ParseError> {
+    let Some(position) = rest.iter().position(|word| word.is(keyword)) else {
+        // Point at a keyword that was used in the wrong place, if there is one.
+        let wrong = ["to", "from"]
+            .into_iter()
+            .filter(|&other| other != keyword)
+            .find_map(|other| rest.iter().skip(1).find(|word| word.is(other)));
+        return Err(match (rest, wrong) {
+            ([], _) => {
+                ParseError::new(format!("expected {before} after '{}'", verb.text), end..end)
+            }
+            (_, Some(wrong)) => ParseError::new(
+                format!("expected '{keyword}', found '{}'", wrong.text),
+                wrong.span.clone(),
+            ),
+            (_, None) => ParseError::new(
+                format!("expected '{keyword}' and {after} after '{}'", phrase(rest)),
+                end..end,
+            ),
+        });
+    };
+
+    let keyword = &rest[position];
+    if position == 0 {
+        return Err(ParseError::new(
+            format!("expected {before} before '{}'", keyword.text),
+            keyword.span.clone(),
+        ));
+    }
+    let remaining = &rest[position + 1..];
+    if remaining.is_empty() {
+        return Err(ParseError::new(
+            format!("expected {after} after '{}'", keyword.text),
+            end..end,
+        ));
+    }
+    Ok((phrase(&rest[..position]), phrase(remaining)))
+}
+
+/// Joins words back up with single spaces.
+fn phrase(words: &[Word]) -> String {
+    words
+        .iter()
+        .map(|word| word.text)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn add(name: &str, department: &str) -> Command {
+        Command::Add {
+            name: name.to_string(),
+            department: department.to_string(),
+        }
+    }
+
+    #[test]
+    fn commands() {
+        assert_eq!(
+            Ok(add("Sally", "Engineering")),
+            parse("Add Sally to Engineering")
+        );
+        assert_eq!(
+            Ok(add("Amir Khan", "Research and Development")),
+            parse("  add   Amir Khan TO Research and Development ")
+        );
+        assert_eq!(
+            Ok(add("Anne To", "Sales")),
+            parse("Add \"Anne To\" to Sales")
+        );
+        assert_eq!(
+            Ok(Command::Remove {
+                name: String::from("Sally"),
+                department: String::from("Engineering")
+            }),
+            parse("Remove Sally from Engineering")
+        );
+        assert_eq!(
+            Ok(Command::Move {
+                name: String::from("Sally"),
+                department: String::from("Sales")
+            }),
+            parse("Move Sally to Sales")
+        );
+        assert_eq!(
+            Ok(Command::List(String::from("Sales"))),
+            parse("List Sales")
+        );
+        assert_eq!(Ok(Command::ListAll), parse("list ALL"));
+        assert_eq!(
+            Ok(Command::List(String::from("all"))),
+            parse("List \"all\"")
+        );
+        assert_eq!(
+            Ok(Command::Rename {
+                from: String::from("Sales"),
+                to: String::from("Marketing")
+            }),
+            parse("Rename Sales to Marketing")
+        );
+        assert_eq!(
+            Ok(Command::Import {
+                path: String::from("new staff.csv"),
+                dry_run: true
+            }),
+            parse("Import \"new staff.csv\" dry run")
+        );
+        assert_eq!(
+            Ok(Command::Import {
+                path: String::from("staff.csv"),
+                dry_run: false
+            }),
+            parse("import staff.csv")
+        );
+        assert_eq!(Ok(Command::Export(None)), parse("Export"));
+        assert_eq!(
+            Ok(Command::Export(Some(String::from("out.csv")))),
+            parse("export out.csv")
+        );
+        assert_eq!(Ok(Command::Undo), parse("undo"));
+        assert_eq!(Ok(Command::Redo), parse("Redo"));
+        assert_eq!(
+            Ok(Command::History(Some(String::from("Sally")))),
+            parse("history Sally")
+        );
+        assert_eq!(Ok(Command::History(None)), parse("History"));
+        assert_eq!(Ok(Command::Help), parse("help"));
+        assert_eq!(Ok(Command::Quit), parse("Quit"));
+    }
+
+    fn error(input: &str) -> (String, String) {
+        let error = parse(input).unwrap_err();
+        let shown = error.show(input);
+        let marker = shown.lines().nth(1).unwrap().to_string();
+        (error.message, marker)
+    }
+
+    #[test]
+    fn errors_point_at_the_problem() {
+        assert_eq!(
+            (
+                String::from(
+                    "unknown command 'Hire', expected one of Add, Remove, Move, List, Rename, \
+                     Import, Export, Undo, Redo, History, Help or Quit"
+                ),
+                String::from("^^^^")
+            ),
+            error("Hire Sally to Sales")
+        );
+        assert_eq!(
+            (
+                String::from("expected 'from', found 'to'"),
+                String::from("             ^^")
+            ),
+            error("Remove Sally to Sales")
+        );
+        assert_eq!(
+            (
+                String::from("expected a name before 'to'"),
+                String::from("    ^^")
+            ),
+            error("Add to Sales")
+        );
+        assert_eq!(
+            (
+                String::from("expected a department after 'to'"),
+                String::from("            ^")
+            ),
+            error("Add Sally to ")
+        );
+        assert_eq!(
+            (
+                String::from("expected a name after 'Add'"),
+                String::from("   ^")
+            ),
+            error("Add")
+        );
+        assert_eq!(
+            (
+                String::from("expected 'to' and a department after 'Sally Sales'"),
+                String::from("               ^")
+            ),
+            error("Add Sally Sales")
+        );
+        assert_eq!(
+            (
+                String::from("'Quit' takes nothing after it"),
+                String::from("     ^^^")
+            ),
+            error("Quit now")
+        );
+        assert_eq!(
+            (
+                String::from("'Undo' takes nothing after it"),
+                String::from("     ^^^^^^^")
+            ),
+            error("Undo the add")
+        );
+        assert_eq!(
+            (
+                String::from("this quote is never closed"),
+                String::from("    ^")
+            ),
+            error("Add \"Sally to Sales")
+        );
+        assert_eq!(
+            (
+                String::from("expected a department or 'all' after 'List'"),
+                String::from("    ^")
+            ),
+            error("List")
+        );
+        assert_eq!(
+            (
+                String::from("expected a file after 'Import'"),
+                String::from("       ^^^")
+            ),
+            error("Import dry run")
+        );
+        assert!(parse("   ").is_err());
+    }
+}
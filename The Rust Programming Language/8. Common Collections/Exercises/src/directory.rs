@@ -0,0 +1,677 @@
+//! The company directory: departments and the employees in them.
+//!
+//! Names keep the capitalization they were given but are looked up without
+//! regard to case, so "sally" finds "Sally". Every employee has a name of
+//! their own and belongs to exactly one department.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct DepartmentId(u64);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct EmployeeId(u64);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Department {
+    pub id: DepartmentId,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Employee {
+    pub id: EmployeeId,
+    pub name: String,
+    pub department: DepartmentId,
+    /// Anything else known about them, such as the extra columns of an
+    /// imported spreadsheet.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub details: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DirectoryError {
+    EmptyName,
+    DepartmentExists(String),
+    NoSuchDepartment(String),
+    /// The employee already works in the named department.
+    EmployeeExists {
+        name: String,
+        department: String,
+    },
+    NoSuchEmployee(String),
+    /// The employee works somewhere else.
+    NotInDepartment {
+        name: String,
+        department: String,
+    },
+    DepartmentNotEmpty(String),
+}
+
+impl fmt::Display for DirectoryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DirectoryError::EmptyName => write!(f, "names can't be empty"),
+            DirectoryError::DepartmentExists(name) => {
+                write!(f, "department '{name}' already exists")
+            }
+            DirectoryError::NoSuchDepartment(name) => write!(f, "there is no department '{name}'"),
+            DirectoryError::EmployeeExists { name, department } => {
+                write!(f, "'{name}' already works in {department}")
+            }
+            DirectoryError::NoSuchEmployee(name) => write!(f, "there is no employee '{name}'"),
+            DirectoryError::NotInDepartment { name, department } => {
+                write!(f, "'{name}' doesn't work in {department}")
+            }
+            DirectoryError::DepartmentNotEmpty(name) => {
+                write!(f, "department '{name}' still has employees")
+            }
+        }
+    }
+}
+
+impl std::error::Error for DirectoryError {}
+
+/// The form names are compared in.
+pub(crate) fn key(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+fn clean(name: &str) -> Result<String, DirectoryError> {
+    let name = name.trim();
+    if name.is_empty() {
+        Err(DirectoryError::EmptyName)
+    } else {
+        Ok(name.to_string())
+    }
+}
+
+/// How a directory is saved: plain lists, rebuilt into maps when loaded.
+#[derive(Serialize, Deserialize)]
+struct Saved {
+    next_id: u64,
+    departments: Vec<Department>,
+    employees: Vec<Employee>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "Saved", into = "Saved")]
+pub struct Directory {
+    departments: BTreeMap<DepartmentId, Department>,
+    /// Department IDs by lowercase name, which also keeps them sorted.
+    department_ids: BTreeMap<String, DepartmentId>,
+    /// Keyed by the lowercase name, which also keeps them sorted.
+    employees: BTreeMap<String, Employee>,
+    next_id: u64,
+}
+
+impl From<Saved> for Directory {
+    fn from(saved: Saved) -> Directory {
+        Directory {
+            department_ids: saved
+                .departments
+                .iter()
+                .map(|department| (key(&department.name), department.id))
+                .collect(),
+            departments: saved
+                .departments
+                .into_iter()
+                .map(|department| (department.id, department))
+                .collect(),
+            employees: saved
+                .employees
+                .into_iter()
+                .map(|employee| (key(&employee.name), employee))
+                .collect(),
+            next_id: saved.next_id,
+        }
+    }
+}
+
+impl From<Directory> for Saved {
+    fn from(directory: Directory) -> Saved {
+        Saved {
+            next_id: directory.next_id,
+            departments: directory
+                .department_ids
+                .values()
+                .filter_map(|id| directory.departments.get(id).cloned())
+                .collect(),
+            employees: directory.employees.into_values().collect(),
+        }
+    }
+}
+
+impl Directory {
+    pub fn new() -> Directory {
+        Directory::default()
+    }
+
+    pub fn department(&self, name: &str) -> Option<&Department> {
+        self.department_ids
+            .get(&key(name))
+            .and_then(|id| self.departments.get(id))
+    }
+
+    pub fn employee(&self, name: &str) -> Option<&Employee> {
+        self.employees.get(&key(name))
+    }
+
+    /// The department `employee` works in, which is always there for an
+    /// employee of this directory.
+    pub fn department_of(&self, employee: &Employee) -> Option<&Department> {
+        self.departments.get(&employee.department)
+    }
+
+    /// The name of the department `employee` works in, or its ID if it isn't
+    /// in this directory.
+    pub(crate) fn department_name(&self, employee: &Employee) -> String {
+        match self.department_of(employee) {
+            Some(department) => department.name.clone(),
+            None => format!("#{}", employee.department.0),
+        }
+    }
+
+    /// Every department, sorted by name.
+    pub fn departments(&self) -> impl Iterator<Item = &Department> {
+        self.department_ids
+            .values()
+            .filter_map(|id| self.departments.get(id))
+    }
+
+    /// The employees of `department`, sorted by name.
+    pub fn employees_in(&self, department: DepartmentId) -> impl Iterator<Item = &Employee> {
+        self.employees
+            .values()
+            .filter(move |employee| employee.department == department)
+    }
+
+    pub fn department_count(&self) -> usize {
+        self.departments.len()
+    }
+
+    pub fn employee_count(&self) -> usize {
+        self.employees.len()
+    }
+
+    /// Whether there are no departments and no employees.
+    pub fn is_empty(&self) -> bool {
+        self.departments.is_empty() && self.employees.is_empty()
+    }
+
+    pub fn add_department(&mut self, name: &str) -> Result<DepartmentId, DirectoryError> {
+        let name = clean(name)?;
+        if let Some(existing) = self.department(&name) {
+            return Err(DirectoryError::DepartmentExists(existing.name.clone()));
+        }
+
+        let id = DepartmentId(self.next_id());
+        self.department_ids.insert(key(&name), id);
+        self.departments.insert(id, Department { id, name });
+        Ok(id)
+    }
+
+    /// Adds an employee to `department`, which is created if it doesn't
+    /// exist yet.
+    pub fn hire(&mut self, name: &str, department: &str) -> Result<EmployeeId, DirectoryError> {
+        self.hire_with_details(name, department, BTreeMap::new())
+    }
+
+    pub fn hire_with_details(
+        &mut self,
+        name: &str,
+        department: &str,
+        details: BTreeMap<String, String>,
+    ) -> Result<EmployeeId, DirectoryError> {
+        let name = clean(name)?;
+        clean(department)?;
+        if let Some(existing) = self.employee(&name) {
+            return Err(DirectoryError::EmployeeExists {
+                name: existing.name.clone(),
+                department: self.department_name(existing),
+            });
+        }
+
+        let department = match self.department(department) {
+            Some(department) => department.id,
+            None => self.add_department(department)?,
+        };
+        let id = EmployeeId(self.next_id());
+        self.employees.insert(
+            key(&name),
+            Employee {
+                id,
+                name,
+                department,
+                details,
+            },
+        );
+        Ok(id)
+    }
+
+    /// Removes an employee, who must work in `department`.
+    pub fn remove(&mut self, name: &str, department: &str) -> Result<Employee, DirectoryError> {
+        let department = self
+            .department(department)
+            .ok_or_else(|| DirectoryError::NoSuchDepartment(department.trim().to_string()))?;
+        let employee = self
+            .employee(name)
+            .ok_or_else(|| DirectoryError::NoSuchEmployee(name.trim().to_string()))?;
+        if employee.department != department.id {
+            return Err(DirectoryError::NotInDepartment {
+                name: employee.name.clone(),
+                department: department.name.clone(),
+            });
+        }
+
+        Ok(self
+            .employees
+            .remove(&key(name))
+            .expect("the employee was just found"))
+    }
+
+    /// Moves an employee to `department`, which is created if it doesn't
+    /// exist yet.
+    pub fn transfer(&mut self, name: &str, department: &str) -> Result<(), DirectoryError> {
+        let employee = self
+            .employee(name)
+            .ok_or_else(|| DirectoryError::NoSuchEmployee(name.trim().to_string()))?;
+        if let Some(current) = self.department(department) {
+            if current.id == employee.department {
+                return Err(DirectoryError::EmployeeExists {
+                    name: employee.name.clone(),
+                    department: current.name.clone(),
+                });
+            }
+        }
+
+        let department = match self.department(department) {
+            Some(department) => department.id,
+            None => self.add_department(department)?,
+        };
+        self.employees
+            .get_mut(&key(name))
+            .expect("the employee was just found")
+            .department = department;
+        Ok(())
+    }
+
+    /// Renames a department, keeping its employees. Changing only the
+    /// capitalization is allowed.
+    pub fn rename_department(&mut self, from: &str, to: &str) -> Result<(), DirectoryError> {
+        let to = clean(to)?;
+        let Some(&id) = self.department_ids.get(&key(from)) else {
+            return Err(DirectoryError::NoSuchDepartment(from.trim().to_string()));
+        };
+        if key(from) != key(&to) {
+            if let Some(existing) = self.department(&to) {
+                return Err(DirectoryError::DepartmentExists(existing.name.clone()));
+            }
+        }
+
+        self.department_ids.remove(&key(from));
+        self.department_ids.insert(key(&to), id);
+        if let Some(department) = self.departments.get_mut(&id) {
+            department.name = to;
+        }
+        Ok(())
+    }
+
+    /// Removes a department with no employees left.
+    pub fn remove_department(&mut self, name: &str) -> Result<Department, DirectoryError> {
+        let department = self
+            .department(name)
+            .ok_or_else(|| DirectoryError::NoSuchDepartment(name.trim().to_string()))?;
+        let id = department.id;
+        if self.employees_in(id).next().is_some() {
+            return Err(DirectoryError::DepartmentNotEmpty(department.name.clone()));
+        }
+
+        self.department_ids.remove(&key(name));
+        Ok(self
+            .departments
+            .remove(&id)
+            .expect("the department was just found"))
+    }
+
+    /// Puts back a department that was removed, with the same ID.
+    pub fn restore_department(&mut self, department: Department) -> Result<(), DirectoryError> {
+        if let Some(existing) = self
+            .department(&department.name)
+            .or_else(|| self.departments.get(&department.id))
+        {
+            return Err(DirectoryError::DepartmentExists(existing.name.clone()));
+        }
+        self.department_ids
+            .insert(key(&department.name), department.id);
+        self.departments.insert(department.id, department);
+        Ok(())
+    }
+
+    /// Puts back an employee who was removed, with the same ID and details.
+    /// Their department must still be there.
+    pub fn restore(&mut self, employee: Employee) -> Result<(), DirectoryError> {
+        if let Some(existing) = self.employee(&employee.name) {
+            return Err(DirectoryError::EmployeeExists {
+                name: existing.name.clone(),
+                department: self.department_name(existing),
+            });
+        }
+        if !self.departments.contains_key(&employee.department) {
+            return Err(DirectoryError::NoSuchDepartment(format!(
+                "#{}",
+                employee.department.0
+            )));
+        }
+        self.employees.insert(key(&employee.name), employee);
+        Ok(())
+    }
+
+    fn next_id(&mut self) -> u64 {
+        self.next_id += 1;
+        self.next_id
+    }
+}
+
+/// One change to the directory, as saved by [`crate::storage`]. Making a
+/// change gives back the changes that undo it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum Change {
+    /// Adds an employee, creating the department if needed.
+    Add {
+        name: String,
+        department: String,
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        details: BTreeMap<String, String>,
+    },
+    Remove {
+        name: String,
+        department: String,
+    },
+    /// Moves an employee, creating the department if needed.
+    Move {
+        name: String,
+        department: String,
+    },
+    Rename {
+        from: String,
+        to: String,
+    },
+    /// Removes an empty department.
+    RemoveDepartment {
+        name: String,
+    },
+    /// Undoes removing an employee.
+    Restore {
+        employee: Employee,
+    },
+    /// Undoes removing a department.
+    RestoreDepartment {
+        department: Department,
+    },
+}
+
+impl Change {
+    /// Makes the change and returns the changes that would undo it, to be
+    /// made in order.
+    pub fn apply(&self, directory: &mut Directory) -> Result<Vec<Change>, DirectoryError> {
+        let created = |directory: &Directory, department: &str| {
+            directory
+                .department(department)
+                .is_none()
+                .then(|| Change::RemoveDepartment {
+                    name: department.trim().to_string(),
+                })
+        };
+
+        let undo = match self {
+            Change::Add {
+                name,
+                department,
+                details,
+            } => {
+                let created = created(directory, department);
+                directory.hire_with_details(name, department, details.clone())?;
+                let employee = directory
+                    .employee(name)
+                    .expect("the employee was just hired");
+                let undo = Change::Remove {
+                    name: employee.name.clone(),
+                    department: directory.department_name(employee),
+                };
+                [Some(undo), created].into_iter().flatten().collect()
+            }
+            Change::Remove { name, department } => {
+                let employee = directory.remove(name, department)?;
+                vec![Change::Restore { employee }]
+            }
+            Change::Move { name, department } => {
+                let from = directory.employee(name).map(|employee| Change::Move {
+                    name: employee.name.clone(),
+                    department: directory.department_name(employee),
+                });
+                let created = created(directory, department);
+                directory.transfer(name, department)?;
+                let undo = from.expect("only employees can be moved");
+                [Some(undo), created].into_iter().flatten().collect()
+            }
+            Change::Rename { from, to } => {
+                let original = directory
+                    .department(from)
+                    .map(|department| department.name.clone());
+                directory.rename_department(from, to)?;
+                vec![Change::Rename {
+                    from: to.trim().to_string(),
+                    to: original.expect("only departments that exist can be renamed"),
+                }]
+            }
+            Change::RemoveDepartment { name } => {
+                let department = directory.remove_department(name)?;
+                vec![Change::RestoreDepartment { department }]
+            }
+            Change::Restore { employee } => {
+                directory.restore(employee.clone())?;
+                vec![Change::Remove {
+                    name: employee.name.clone(),
+                    department: directory.department_name(employee),
+                }]
+            }
+            Change::RestoreDepartment { department } => {
+                directory.restore_department(department.clone())?;
+                vec![Change::RemoveDepartment {
+                    name: department.name.clone(),
+                }]
+            }
+        };
+        Ok(undo)
+    }
+
+    /// Whether the change is about the employee or department `name`.
+    pub fn mentions(&self, name: &str) -> bool {
+        let name = key(name);
+        let names: Vec<&str> = match self {
+            Change::Add {
+                name, department, ..
+            }
+            | Change::Remove { name, department }
+            | Change::Move { name, department } => vec![name, department],
+            Change::Rename { from, to } => vec![from, to],
+            Change::RemoveDepartment { name } => vec![name],
+            Change::Restore { employee } => vec![&employee.name],
+            Change::RestoreDepartment { department } => vec![&department.name],
+        };
+        names.into_iter().any(|other| key(other) == name)
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Change::Add {
+                name, department, ..
+            } => write!(f, "Add {name} to {department}"),
+            Change::Remove { name, department } => write!(f, "Remove {name} from {department}"),
+            Change::Move { name, department } => write!(f, "Move {name} to {department}"),
+            Change::Rename { from, to } => write!(f, "Rename {from} to {to}"),
+            Change::RemoveDepartment { name } => write!(f, "Remove department {name}"),
+            Change::Restore { employee } => write!(f, "Restore {}", employee.name),
+            Change::RestoreDepartment { department } => {
+                write!(f, "Restore department {}", department.name)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names<'a>(directory: &'a Directory, department: &str) -> Vec<&'a str> {
+        let department = directory.department(department).unwrap();
+        directory
+            .employees_in(department.id)
+            .map(|employee| employee.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn lookups_ignore_case_but_names_keep_it() {
+        let mut directory = Directory::new();
+        let sally = directory.hire("Sally", "Engineering").unwrap();
+        directory.hire(" amir  ", "engineering").unwrap();
+
+        let employee = directory.employee("SALLY").unwrap();
+        assert_eq!((sally, "Sally"), (employee.id, employee.name.as_str()));
+        assert_eq!(
+            "Engineering",
+            directory.department_of(employee).unwrap().name
+        );
+        assert_eq!(vec!["amir", "Sally"], names(&directory, "ENGINEERING"));
+        assert_eq!(1, directory.departments().count());
+    }
+
+    #[test]
+    fn uniqueness() {
+        let mut directory = Directory::new();
+        directory.hire("Sally", "Engineering").unwrap();
+
+        assert_eq!(
+            Err(DirectoryError::EmployeeExists {
+                name: String::from("Sally"),
+                department: String::from("Engineering")
+            }),
+            directory.hire("sally", "Sales")
+        );
+        assert_eq!(
+            Err(DirectoryError::EmptyName),
+            directory.hire("  ", "Sales")
+        );
+        assert_eq!(Err(DirectoryError::EmptyName), directory.hire("Amir", ""));
+        assert_eq!(
+            Err(DirectoryError::DepartmentExists(String::from(
+                "Engineering"
+            ))),
+            directory.add_department("engineering")
+        );
+        assert!(directory.department("Sales").is_none());
+    }
+
+    #[test]
+    fn ids_stay_put() {
+        let mut directory = Directory::new();
+        let sally = directory.hire("Sally", "Engineering").unwrap();
+        let engineering = directory.department("Engineering").unwrap().id;
+
+        directory.transfer("sally", "Sales").unwrap();
+        directory.rename_department("engineering", "R&D").unwrap();
+        directory.rename_department("sales", "SALES").unwrap();
+
+        assert_eq!(engineering, directory.department("r&d").unwrap().id);
+        let employee = directory.employee("Sally").unwrap();
+        assert_eq!(sally, employee.id);
+        assert_eq!("SALES", directory.department_of(employee).unwrap().name);
+    }
+
+    #[test]
+    fn counts() {
+        let mut directory = Directory::new();
+        assert!(directory.is_empty());
+
+        directory.add_department("Sales").unwrap();
+        directory.hire("Sally", "Engineering").unwrap();
+        assert_eq!(2, directory.department_count());
+        assert_eq!(1, directory.employee_count());
+
+        directory.remove("Sally", "Engineering").unwrap();
+        assert_eq!(0, directory.employee_count());
+        assert!(!directory.is_empty());
+
+        directory.remove_department("Engineering").unwrap();
+        directory.remove_department("Sales").unwrap();
+        assert_eq!(0, directory.department_count());
+        assert!(directory.is_empty());
+    }
+
+    #[test]
+    fn department_of_another_directory() {
+        let mut directory = Directory::new();
+        directory.hire("Sally", "Engineering").unwrap();
+        let sally = directory.employee("Sally").unwrap().clone();
+
+        assert_eq!(None, Directory::new().department_of(&sally));
+        directory.rename_department("Engineering", "R&D").unwrap();
+        assert_eq!("R&D", directory.department_of(&sally).unwrap().name);
+    }
+
+    #[test]
+    fn removing_and_moving() {
+        let mut directory = Directory::new();
+        directory.hire("Sally", "Engineering").unwrap();
+        directory.add_department("Sales").unwrap();
+
+        assert_eq!(
+            Err(DirectoryError::NotInDepartment {
+                name: String::from("Sally"),
+                department: String::from("Sales")
+            }),
+            directory.remove("sally", "sales")
+        );
+        assert_eq!(
+            Err(DirectoryError::NoSuchDepartment(String::from("Legal"))),
+            directory.remove("Sally", "Legal")
+        );
+        assert_eq!(
+            Err(DirectoryError::NoSuchEmployee(String::from("Amir"))),
+            directory.transfer("Amir", "Sales")
+        );
+        assert!(directory.transfer("Sally", "engineering").is_err());
+        assert_eq!(
+            Err(DirectoryError::DepartmentExists(String::from("Sales"))),
+            directory.rename_department("Engineering", "sales")
+        );
+
+        assert_eq!(
+            "Sally",
+            directory.remove("SALLY", "Engineering").unwrap().name
+        );
+        assert!(directory.employee("Sally").is_none());
+        assert!(names(&directory, "Engineering").is_empty());
+    }
+
+    #[test]
+    fn saved_as_lists() {
+        let mut directory = Directory::new();
+        directory.hire("Sally", "Engineering").unwrap();
+
+        let json = serde_json::to_string(&directory).unwrap();
+        assert_eq!(
+            r#"{"next_id":2,"departments":[{"id":1,"name":"Engineering"}],"employees":[{"id":2,"name":"Sally","department":1}]}"#,
+            json
+        );
+        assert_eq!(directory, serde_json::from_str(&json).unwrap());
+    }
+}
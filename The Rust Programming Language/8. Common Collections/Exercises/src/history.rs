@@ -0,0 +1,633 @@
+//! Undo, redo and an audit log of every change made to the directory.
+//!
+//! Changes are made in edits: everything one command did, undone and redone
+//! together. Each edit is saved as soon as it is made, and taken back if it
+//! can't be. The audit log records each edit with the time it was made and
+//! can be kept in a file of JSON lines.
+
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::directory::{Change, Directory, DirectoryError};
+use crate::storage::Storage;
+
+#[derive(Debug)]
+pub enum HistoryError {
+    Directory(DirectoryError),
+    /// The edit couldn't be saved.
+    Save(io::Error),
+    /// The audit log couldn't be written.
+    Io(io::Error),
+    NothingToUndo,
+    NothingToRedo,
+}
+
+impl fmt::Display for HistoryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HistoryError::Directory(e) => write!(f, "{e}"),
+            HistoryError::Save(e) => write!(f, "failed to save, so nothing was changed: {e}"),
+            HistoryError::Io(e) => write!(f, "failed to write the audit log: {e}"),
+            HistoryError::NothingToUndo => write!(f, "there is nothing to undo"),
+            HistoryError::NothingToRedo => write!(f, "there is nothing to redo"),
+        }
+    }
+}
+
+impl std::error::Error for HistoryError {}
+
+impl From<DirectoryError> for HistoryError {
+    fn from(e: DirectoryError) -> HistoryError {
+        HistoryError::Directory(e)
+    }
+}
+
+impl From<io::Error> for HistoryError {
+    fn from(e: io::Error) -> HistoryError {
+        HistoryError::Io(e)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    Do,
+    Undo,
+    Redo,
+}
+
+/// An entry of the audit log.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Record {
+    pub time: SystemTime,
+    pub action: Action,
+    /// The changes that were made, which for an undo are the ones that
+    /// reversed the edit.
+    pub changes: Vec<Change>,
+}
+
+impl fmt::Display for Record {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}  ", format_utc(self.time))?;
+        match self.action {
+            Action::Do => {}
+            Action::Undo => write!(f, "Undo: ")?,
+            Action::Redo => write!(f, "Redo: ")?,
+        }
+        for (i, change) in self.changes.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{change}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Formats `time` as "YYYY-MM-DD HH:MM:SS UTC".
+fn format_utc(time: SystemTime) -> String {
+    let seconds = time
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs());
+    let (days, seconds) = (seconds / 86_400, seconds % 86_400);
+
+    // Howard Hinnant's conversion from days since 1970 to a civil date.
+    let days = days as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02} UTC",
+        seconds / 3_600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+/// Changes that were made together, and the changes that undo them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Edit {
+    changes: Vec<Change>,
+    undo: Vec<Change>,
+}
+
+impl Edit {
+    /// Makes all of `changes` or, if one fails, none of them.
+    fn make(directory: &mut Directory, changes: Vec<Change>) -> Result<Edit, DirectoryError> {
+        let mut undo: Vec<Vec<Change>> = Vec::new();
+        for change in &changes {
+            match change.apply(directory) {
+                Ok(reverse) => undo.push(reverse),
+                Err(e) => {
+                    for change in undo.iter().rev().flatten() {
+                        change
+                            .apply(directory)
+                            .expect("undoing a change that was just made");
+                    }
+                    return Err(e);
+                }
+            }
+        }
+
+        Ok(Edit {
+            changes,
+            undo: undo.into_iter().rev().flatten().collect(),
+        })
+    }
+}
+
+/// The undo and redo stacks, and the audit log.
+#[derive(Debug, Default)]
+pub struct History {
+    done: Vec<Edit>,
+    undone: Vec<Edit>,
+    records: Vec<Record>,
+    audit_file: Option<PathBuf>,
+}
+
+impl History {
+    pub fn new() -> History {
+        History::default()
+    }
+
+    /// Keeps the audit log in `path`, reading what is already there.
+    pub fn with_audit_file(path: impl Into<PathBuf>) -> io::Result<History> {
+        let path = path.into();
+        let mut records = Vec::new();
+        match fs::read_to_string(&path) {
+            Ok(text) => {
+                // A crash while writing can leave the last line unfinished.
+                // It is cut off so the next record starts on a line of its own.
+                let complete = text.rfind('\n').map_or(0, |i| i + 1);
+                if complete < text.len() {
+                    OpenOptions::new()
+                        .write(true)
+                        .open(&path)?
+                        .set_len(complete as u64)?;
+                }
+                for line in text[..complete].lines().filter(|line| !line.is_empty()) {
+                    records.push(serde_json::from_str(line)?);
+                }
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+
+        Ok(History {
+            records,
+            audit_file: Some(path),
+            ..History::default()
+        })
+    }
+
+    /// Makes `changes` as one edit, all or nothing, and saves them to
+    /// `storage`. Returns the changes that were saved.
+    pub fn apply(
+        &mut self,
+        directory: &mut Directory,
+        storage: &mut dyn Storage,
+        changes: Vec<Change>,
+    ) -> Result<&[Change], HistoryError> {
+        let edit = Edit::make(directory, changes)?;
+        let edit = self.commit(directory, storage, Action::Do, edit)?;
+        self.undone.clear();
+        self.done.push(edit);
+        Ok(&self.done[self.done.len() - 1].changes)
+    }
+
+    /// Undoes the last edit and saves the changes that undid it, which are
+    /// returned.
+    pub fn undo(
+        &mut self,
+        directory: &mut Directory,
+        storage: &mut dyn Storage,
+    ) -> Result<&[Change], HistoryError> {
+        let edit = self.done.last().ok_or(HistoryError::NothingToUndo)?;
+        let undo = Edit::make(directory, edit.undo.clone())?;
+        let undo = self.commit(directory, storage, Action::Undo, undo)?;
+        self.done.pop();
+        self.undone.push(undo);
+        Ok(&self.undone[self.undone.len() - 1].changes)
+    }
+
+    /// Makes the last undone edit again and saves the changes that did it,
+    /// which are returned.
+    pub fn redo(
+        &mut self,
+        directory: &mut Directory,
+        storage: &mut dyn Storage,
+    ) -> Result<&[Change], HistoryError> {
+        let edit = self.undone.last().ok_or(HistoryError::NothingToRedo)?;
+        let redo = Edit::make(directory, edit.undo.clone())?;
+        let redo = self.commit(directory, storage, Action::Redo, redo)?;
+        self.undone.pop();
+        self.done.push(redo);
+        Ok(&self.done[self.done.len() - 1].changes)
+    }
+
+    /// Records `edit`, which has been made to `directory`, in the audit log
+    /// and then saves it. An edit that can't be recorded or saved is taken
+    /// back out of memory and the log, so nothing is left half done.
+    fn commit(
+        &mut self,
+        directory: &mut Directory,
+        storage: &mut dyn Storage,
+        action: Action,
+        edit: Edit,
+    ) -> Result<Edit, HistoryError> {
+        let record = Record {
+            time: SystemTime::now(),
+            action,
+            changes: edit.changes.clone(),
+        };
+        let recorded = match &self.audit_file {
+            Some(path) => match append(path, &record) {
+                Ok(length) => Some((path, length)),
+                Err(e) => {
+                    Edit::make(directory, edit.undo)?;
+                    return Err(e.into());
+                }
+            },
+            None => None,
+        };
+
+        if let Err(e) = storage.save(directory, &edit.changes) {
+            Edit::make(directory, edit.undo)?;
+            if let Some((path, length)) = recorded {
+                truncate(path, length).map_err(|t| {
+                    io::Error::new(
+                        t.kind(),
+                        format!("{t}, so it still has an edit that failed to save: {e}"),
+                    )
+                })?;
+            }
+            return Err(HistoryError::Save(e));
+        }
+        self.records.push(record);
+        Ok(edit)
+    }
+
+    /// Every edit made, oldest first.
+    pub fn records(&self) -> &[Record] {
+        &self.records
+    }
+
+    /// The edits that involved the employee or department `name`.
+    pub fn mentioning<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Record> {
+        self.records
+            .iter()
+            .filter(move |record| record.changes.iter().any(|change| change.mentions(name)))
+    }
+}
+
+/// Adds `record` to the end of the audit file as a line of JSON, and returns
+/// the length the file had before. A record that is only partly written is
+/// cut off again.
+fn append(path: &Path, record: &Record) -> io::Result<u64> {
+    let mut line = serde_json::to_vec(record)?;
+    line.push(b'\n');
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    let length = file.metadata()?.len();
+    if let Err(e) = file.write_all(&line) {
+        // If this fails too, loading the file will drop the unfinished line.
+        let _ = file.set_len(length);
+        return Err(e);
+    }
+    Ok(length)
+}
+
+/// Cuts the audit file back to `length`, taking back what was appended since.
+fn truncate(path: &Path, length: u64) -> io::Result<()> {
+    OpenOptions::new().write(true).open(path)?.set_len(length)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+    use std::env;
+    use std::time::Duration;
+
+    fn add(name: &str, department: &str) -> Change {
+        Change::Add {
+            name: name.to_string(),
+            department: department.to_string(),
+            details: BTreeMap::new(),
+        }
+    }
+
+    /// Keeps what was saved in memory, or fails to save anything.
+    #[derive(Default)]
+    struct Saved {
+        changes: Vec<Change>,
+        broken: bool,
+    }
+
+    impl Storage for Saved {
+        fn load(&mut self) -> io::Result<Directory> {
+            Ok(Directory::new())
+        }
+
+        fn save(&mut self, _directory: &Directory, changes: &[Change]) -> io::Result<()> {
+            if self.broken {
+                return Err(io::Error::other("disk full"));
+            }
+            self.changes.extend_from_slice(changes);
+            Ok(())
+        }
+    }
+
+    fn remove(name: &str, department: &str) -> Change {
+        Change::Remove {
+            name: name.to_string(),
+            department: department.to_string(),
+        }
+    }
+
+    #[test]
+    fn undo_and_redo() {
+        let mut directory = Directory::new();
+        let mut history = History::new();
+        let mut saved = Saved::default();
+        history
+            .apply(
+                &mut directory,
+                &mut saved,
+                vec![add("Sally", "Engineering")],
+            )
+            .unwrap();
+        let sally = directory.employee("Sally").unwrap().clone();
+
+        history
+            .apply(
+                &mut directory,
+                &mut saved,
+                vec![
+                    Change::Move {
+                        name: String::from("sally"),
+                        department: String::from("Sales"),
+                    },
+                    remove("Sally", "Sales"),
+                ],
+            )
+            .unwrap();
+        assert!(directory.employee("Sally").is_none());
+
+        // Undoing puts Sally back as she was, ID and all, and the department
+        // the move made is gone again.
+        let undo: Vec<String> = history
+            .undo(&mut directory, &mut saved)
+            .unwrap()
+            .iter()
+            .map(Change::to_string)
+            .collect();
+        assert_eq!(sally, *directory.employee("Sally").unwrap());
+        assert!(directory.department("Sales").is_none());
+        assert_eq!(
+            vec![
+                "Restore Sally",
+                "Move Sally to Engineering",
+                "Remove department Sales"
+            ],
+            undo
+        );
+
+        history.redo(&mut directory, &mut saved).unwrap();
+        assert!(directory.employee("Sally").is_none());
+        assert!(directory.department("Sales").is_some());
+
+        history.undo(&mut directory, &mut saved).unwrap();
+        history.undo(&mut directory, &mut saved).unwrap();
+        assert!(directory.is_empty());
+        assert!(matches!(
+            history.undo(&mut directory, &mut saved),
+            Err(HistoryError::NothingToUndo)
+        ));
+
+        // Anything new clears what could have been redone.
+        history
+            .apply(&mut directory, &mut saved, vec![add("Amir", "Sales")])
+            .unwrap();
+        assert!(matches!(
+            history.redo(&mut directory, &mut saved),
+            Err(HistoryError::NothingToRedo)
+        ));
+    }
+
+    #[test]
+    fn edits_are_all_or_nothing() {
+        let mut directory = Directory::new();
+        let mut history = History::new();
+        let mut saved = Saved::default();
+        let error = history
+            .apply(
+                &mut directory,
+                &mut saved,
+                vec![add("Sally", "Engineering"), add("sally", "Sales")],
+            )
+            .unwrap_err();
+
+        assert_eq!("'Sally' already works in Engineering", error.to_string());
+        assert!(directory.is_empty());
+        assert!(saved.changes.is_empty());
+        assert!(history.records().is_empty());
+    }
+
+    #[test]
+    fn edits_that_cant_be_saved_are_taken_back() {
+        let mut directory = Directory::new();
+        let mut history = History::new();
+        let mut saved = Saved::default();
+        history
+            .apply(
+                &mut directory,
+                &mut saved,
+                vec![add("Sally", "Engineering")],
+            )
+            .unwrap();
+        history
+            .apply(&mut directory, &mut saved, vec![add("Amir", "Sales")])
+            .unwrap();
+        history.undo(&mut directory, &mut saved).unwrap();
+        let before = directory.clone();
+
+        saved.broken = true;
+        let error = history
+            .apply(
+                &mut directory,
+                &mut saved,
+                vec![remove("Sally", "Engineering")],
+            )
+            .unwrap_err();
+        assert_eq!(
+            "failed to save, so nothing was changed: disk full",
+            error.to_string()
+        );
+        assert!(history.undo(&mut directory, &mut saved).is_err());
+        assert!(history.redo(&mut directory, &mut saved).is_err());
+        assert_eq!(before, directory);
+        assert_eq!(3, history.records().len());
+
+        // Both stacks are as they were before the failures.
+        saved.broken = false;
+        history.redo(&mut directory, &mut saved).unwrap();
+        assert!(directory.employee("Amir").is_some());
+        history.undo(&mut directory, &mut saved).unwrap();
+        history.undo(&mut directory, &mut saved).unwrap();
+        assert!(directory.is_empty());
+    }
+
+    #[test]
+    fn audit_log() {
+        let path = env::temp_dir().join(format!("exercises-audit-{}", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let mut directory = Directory::new();
+        let mut history = History::with_audit_file(&path).unwrap();
+        let mut saved = Saved::default();
+
+        history
+            .apply(
+                &mut directory,
+                &mut saved,
+                vec![add("Sally", "Engineering")],
+            )
+            .unwrap();
+        history
+            .apply(&mut directory, &mut saved, vec![add("Amir", "Sales")])
+            .unwrap();
+        history.undo(&mut directory, &mut saved).unwrap();
+        history
+            .apply(
+                &mut directory,
+                &mut saved,
+                vec![Change::Rename {
+                    from: String::from("engineering"),
+                    to: String::from("R&D"),
+                }],
+            )
+            .unwrap();
+
+        let reopened = History::with_audit_file(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(history.records(), reopened.records());
+
+        let sally: Vec<String> = reopened
+            .mentioning("SALLY")
+            .map(|record| record.to_string()[25..].to_string())
+            .collect();
+        assert_eq!(vec!["Add Sally to Engineering"], sally);
+        let amir: Vec<String> = reopened
+            .mentioning("amir")
+            .map(|record| record.to_string()[25..].to_string())
+            .collect();
+        assert_eq!(
+            vec![
+                "Add Amir to Sales",
+                "Undo: Remove Amir from Sales, Remove department Sales"
+            ],
+            amir
+        );
+        assert_eq!(2, reopened.mentioning("Engineering").count());
+    }
+
+    #[test]
+    fn edits_that_cant_be_saved_are_not_audited() {
+        let path = env::temp_dir().join(format!("exercises-unsaved-audit-{}", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let mut directory = Directory::new();
+        let mut history = History::with_audit_file(&path).unwrap();
+        let mut saved = Saved::default();
+        history
+            .apply(&mut directory, &mut saved, vec![add("Sally", "Sales")])
+            .unwrap();
+        let audited = fs::read(&path).unwrap();
+
+        saved.broken = true;
+        let error = history
+            .apply(&mut directory, &mut saved, vec![add("Amir", "Sales")])
+            .unwrap_err();
+        assert!(matches!(error, HistoryError::Save(_)));
+        assert_eq!(audited, fs::read(&path).unwrap());
+        fs::remove_file(&path).unwrap();
+        assert!(directory.employee("Amir").is_none());
+        assert_eq!(1, history.records().len());
+    }
+
+    #[test]
+    fn edits_that_cant_be_audited_are_taken_back() {
+        let path = env::temp_dir()
+            .join(format!("exercises-missing-{}", std::process::id()))
+            .join("audit");
+        let mut directory = Directory::new();
+        let mut history = History::with_audit_file(&path).unwrap();
+        let mut saved = Saved::default();
+
+        let error = history
+            .apply(&mut directory, &mut saved, vec![add("Sally", "Sales")])
+            .unwrap_err();
+        assert!(matches!(error, HistoryError::Io(_)));
+        assert!(directory.is_empty());
+        assert!(saved.changes.is_empty());
+        assert!(history.records().is_empty());
+        assert!(history.undo(&mut directory, &mut saved).is_err());
+    }
+
+    #[test]
+    fn unfinished_audit_line_is_dropped() {
+        let path = env::temp_dir().join(format!("exercises-torn-audit-{}", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let mut directory = Directory::new();
+        let mut history = History::with_audit_file(&path).unwrap();
+        let mut saved = Saved::default();
+        history
+            .apply(&mut directory, &mut saved, vec![add("Sally", "Sales")])
+            .unwrap();
+
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"{\"time\":17").unwrap();
+
+        let mut reopened = History::with_audit_file(&path).unwrap();
+        assert_eq!(history.records(), reopened.records());
+        reopened
+            .apply(&mut directory, &mut saved, vec![add("Amir", "Sales")])
+            .unwrap();
+        let loaded = History::with_audit_file(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(reopened.records(), loaded.records());
+        assert_eq!(2, loaded.records().len());
+    }
+
+    #[test]
+    fn timestamps() {
+        let record = Record {
+            time: UNIX_EPOCH + Duration::from_secs(1_792_281_600 + 3_723),
+            action: Action::Redo,
+            changes: vec![add("Sally", "Engineering")],
+        };
+        assert_eq!(
+            "2026-10-18 01:02:03 UTC  Redo: Add Sally to Engineering",
+            record.to_string()
+        );
+        assert_eq!("1970-01-01 00:00:00 UTC", format_utc(UNIX_EPOCH));
+        assert_eq!(
+            "2000-02-29 23:59:59 UTC",
+            format_utc(UNIX_EPOCH + Duration::from_secs(951_868_799))
+        );
+    }
+}
@@ -0,0 +1,13 @@
+//! Reusable pieces of the chapter 8 exercises, which `main.rs` runs.
+
+pub mod command;
+pub mod directory;
+pub mod history;
+pub mod pig_latin;
+pub mod spreadsheet;
+pub mod statistics;
+pub mod stats_cli;
+pub mod storage;
+pub mod streaming;
+pub mod transform;
+pub mod transform_cli;
@@ -1,5 +1,20 @@
-use std::collections::HashMap;
-use std::io::{stdout, stdin, Write};
+use std::collections::BTreeMap;
+use std::env;
+use std::error::Error;
+use std::fmt::Display;
+use std::fs::File;
+use std::io::{stdin, stdout, Write};
+use std::process;
+
+use exercises::command::{self, Command};
+use exercises::directory::{Change, Directory, DirectoryError};
+use exercises::history::History;
+use exercises::spreadsheet;
+use exercises::statistics;
+use exercises::storage;
+use exercises::transform::{PigLatin, WordTransform};
+
+const COMPANY_FILE: &str = "company_directory.log";
 
 fn main() {
     print_separator();
@@ -29,55 +44,20 @@ fn exercise_1() {
     the list.
      */
 
-    let mut values = vec![
+    let values = [
         1, 2, 3, 4, 5, 6, 7, 5, 5, 5, 5, 5, 5, 5, 8, 9, 10, 1, 2, 4, 6, 3, 1, 4, 61, 74, 3,
     ];
-    let mean = exercise_1_mean(&mut values);
-    let median = exercise_1_median(&mut values);
-    let mode = exercise_1_mode(&mut values);
+    // The list is not empty, so every statistic exists.
+    let mean = statistics::mean(&values).unwrap();
+    let median = statistics::median(&values).unwrap();
+    let modes = statistics::modes(&values).unwrap();
 
     println!(
-        "Exercise 1:\nValues: {:?}\nMean: {}\tMedian: {}\tMode: {:?}",
-        values, mean, median, mode
+        "Exercise 1:\nValues: {:?}\nMean: {}\tMedian: {}\tModes: {:?}",
+        values, mean, median, modes
     );
 }
 
-fn exercise_1_mean(values: &mut Vec<i32>) -> i32 {
-    let values_len = values.len() as i32;
-    let mut mean = 0;
-    for value in values {
-        mean += *value;
-    }
-    mean / values_len
-}
-
-fn exercise_1_median(values: &mut Vec<i32>) -> i32 {
-    values.sort();
-    values[values.len() / 2]
-}
-
-fn exercise_1_mode(values: &mut Vec<i32>) -> (i32, i32) {
-    let mut map: HashMap<&i32, i32> = HashMap::new();
-    for element in values {
-        let count = map.entry(element).or_insert(0);
-        *count += 1;
-    }
-    let mut mode: Option<(i32, i32)> = None;
-    for (key, value) in map.iter() {
-        match mode {
-            Some((_, stored_value)) if value > &stored_value => {
-                mode = Some((**key, *value));
-            }
-            Some(_) => {}
-            None => mode = Some((**key, *value)),
-        }
-    }
-    match mode {
-        Some(_) => mode.unwrap(),
-        None => (-1, -1),
-    }
-}
-
 // ********************************************************
 fn exercise_2() {
     /*
@@ -86,26 +66,11 @@ fn exercise_2() {
     to the end instead (“apple” becomes “apple-hay”). Keep in mind the details about UTF-8 encoding!
      */
 
-    let original_string = String::from("Mary had a little lamb");
-    let new_string = exercise_2_convert_string(&original_string);
+    let original_string = String::from("Mary had a little lamb, whose fleece was white as snow.");
+    let new_string = PigLatin.apply(&original_string);
     println!("Original string: {original_string}\nNew string: {new_string}")
 }
 
-fn exercise_2_convert_string(original_string: &String) -> String {
-    let vowels = vec!['a', 'e', 'i', 'o', 'u'];
-    let mut returned_string = String::new();
-    'first_loop: for word in original_string.split_whitespace() {
-        if let Some(first_char) = word.chars().next() {
-            if vowels.contains(&first_char) {
-                returned_string.push_str(&format!("{word}-hay "));
-                continue 'first_loop;
-            }
-        }
-        returned_string.push_str(&format!("{}-fay ", &word[0..]));
-    }
-    returned_string.trim_end().to_string()
-}
-
 // ********************************************************
 fn exercise_3() {
     /*
@@ -115,102 +80,180 @@ fn exercise_3() {
     department, sorted alphabetically.
      */
 
-    let mut company: HashMap<String, Vec<String>> = HashMap::new();
+    let path = env::args()
+        .nth(1)
+        .unwrap_or_else(|| String::from(COMPANY_FILE));
+    let mut storage = storage::open(&path);
+    let mut directory = storage.load().unwrap_or_else(|e| {
+        print_error(&format!("Failed to load '{path}': {e}"));
+        process::exit(1);
+    });
+    let audit_path = format!("{path}.audit");
+    let mut history = History::with_audit_file(&audit_path).unwrap_or_else(|e| {
+        print_error(&format!("Failed to load '{audit_path}': {e}"));
+        process::exit(1);
+    });
     let mut input = String::new();
-    print_options();
+    println!("{}", command::HELP);
     loop {
-        get_user_input(&mut input);
-        let choice: i32 = match input.trim().parse() {
-            Ok(value) => value,
-            Err(_) => continue,
+        print!("> ");
+        stdout().flush().expect("Failed to flush stdout");
+        if !get_user_input(&mut input) {
+            break;
+        }
+        if input.trim().is_empty() {
+            continue;
+        }
+        let command = match command::parse(&input) {
+            Ok(command) => command,
+            Err(error) => {
+                print_error(&format!("{error}\n{}", error.show(&input)));
+                continue;
+            }
+        };
+        let changes = match command {
+            Command::Add { name, department } => vec![Change::Add {
+                name,
+                department,
+                details: BTreeMap::new(),
+            }],
+            Command::Remove { name, department } => vec![Change::Remove { name, department }],
+            Command::Move { name, department } => vec![Change::Move { name, department }],
+            Command::Rename { from, to } => vec![Change::Rename { from, to }],
+            Command::Import { path, dry_run } => {
+                match exercise_3_import(&directory, &path, dry_run) {
+                    Ok(changes) => changes,
+                    Err(e) => {
+                        print_error(&format!("Failed to import '{path}': {e}"));
+                        continue;
+                    }
+                }
+            }
+            Command::Export(path) => {
+                if let Err(e) = exercise_3_export(&directory, path.as_deref()) {
+                    print_error(&format!("Failed to export: {e}"));
+                }
+                continue;
+            }
+            Command::List(department) => {
+                exercise_3_list_people_department(&directory, &department);
+                continue;
+            }
+            Command::ListAll => {
+                exercise_3_list_people_company(&directory);
+                continue;
+            }
+            Command::History(name) => {
+                match name {
+                    Some(name) => history.mentioning(&name).for_each(|r| println!("{r}")),
+                    None => history.records().iter().for_each(|r| println!("{r}")),
+                }
+                continue;
+            }
+            Command::Undo => {
+                if let Err(error) = history.undo(&mut directory, &mut *storage) {
+                    print_error(&error);
+                }
+                continue;
+            }
+            Command::Redo => {
+                if let Err(error) = history.redo(&mut directory, &mut *storage) {
+                    print_error(&error);
+                }
+                continue;
+            }
+            Command::Help => {
+                println!("{}", command::HELP);
+                continue;
+            }
+            Command::Quit => break,
         };
-        match choice {
-            1 => exercise_3_list_people_company(&mut company),
-            2 => exercise_3_list_departments(&mut company),
-            3 => exercise_3_list_people_department(&mut company),
-            4 => exercise_3_add_department(&mut company),
-            5 => exercise_3_add_people_department(&mut company),
-            6 => break,
-            _ => print_options(),
+        if !changes.is_empty() {
+            if let Err(error) = history.apply(&mut directory, &mut *storage, changes) {
+                print_error(&error);
+            }
         }
     }
 }
 
-fn get_user_input(input: &mut String) {
-    input.clear();
-    stdin()
-        .read_line(input)
-        .expect("Failed to read user input");
-    input.pop();
-    input.make_ascii_lowercase();
-}
-
-fn print_options() {
+/// Reports what importing `path` would do, and returns the changes to make
+/// unless it's a `dry_run`.
+fn exercise_3_import(
+    directory: &Directory,
+    path: &str,
+    dry_run: bool,
+) -> Result<Vec<Change>, Box<dyn Error>> {
+    let import = spreadsheet::plan_import(directory, File::open(path)?)?;
+    for issue in &import.issues {
+        println!("Skipped {issue}");
+    }
+    if dry_run {
+        for change in &import.changes {
+            if let Change::Add {
+                name, department, ..
+            } = change
+            {
+                println!("Would add {name} to {department}");
+            }
+        }
+        println!(
+            "Dry run: {} to add, {} skipped",
+            import.changes.len(),
+            import.issues.len()
+        );
+        return Ok(Vec::new());
+    }
     println!(
-        "\
-    1. List all people in company\n\
-    2. List departments\n\
-    3. List all people in a department\n\
-    4. Add department\n\
-    5. Add employee to department\n\
-    6. Quit"
-    )
+        "Imported {}, {} skipped",
+        import.changes.len(),
+        import.issues.len()
+    );
+    Ok(import.changes)
 }
 
-fn exercise_3_list_people_company(company: &mut HashMap<String, Vec<String>>) {
-    for (department, all_employees) in company {
-        all_employees.sort();
-        print!("{department}:");
-        for employee in all_employees {
-            print!(" {employee}");
+fn exercise_3_export(directory: &Directory, path: Option<&str>) -> Result<(), Box<dyn Error>> {
+    match path {
+        Some(path) => {
+            spreadsheet::export(directory, File::create(path)?)?;
+            println!("Exported to {path}");
         }
-        println!();
+        None => spreadsheet::export(directory, stdout())?,
     }
+    Ok(())
 }
 
-fn exercise_3_list_departments(company: &mut HashMap<String, Vec<String>>) {
-    println!("{:?}", &company.keys());
+fn print_error(error: &dyn Display) {
+    eprintln!("\x1B[31mERROR\x1B[0m: {error}");
 }
 
-fn exercise_3_list_people_department(company: &mut HashMap<String, Vec<String>>) {
-    let mut department = String::new();
-    get_user_input(&mut department);
-    match company.get(&department) {
-        Some(names) => {
-            println!("{department} = {:?}", names);
-        }
-        None => {
-            eprintln!("\x1B[31mERROR\x1B[0m: Department does not exists inside company.");
-        }
-    }
+/// Reads a line without its line ending, returning `false` at the end of input.
+fn get_user_input(input: &mut String) -> bool {
+    input.clear();
+    let read = stdin().read_line(input).expect("Failed to read user input");
+    let len = input.trim_end_matches(['\r', '\n']).len();
+    input.truncate(len);
+    read > 0
 }
 
-fn exercise_3_add_department(company: &mut HashMap<String, Vec<String>>) {
-    let mut department = String::new();
-    print!("Department: ");
-    stdout().flush().expect("\x1B[31mERROR\x1B[0m: Failed to flush stdout");
-    get_user_input(&mut department);
-    match &company.contains_key(&department) {
-        false => { company.insert(department.clone(), Vec::new()); }
-        true => eprintln!("\x1B[31mERROR\x1B[0m: Department '{}' already exists", department)
-    };
+fn exercise_3_list_people_company(directory: &Directory) {
+    for department in directory.departments() {
+        print!("{}:", department.name);
+        for employee in directory.employees_in(department.id) {
+            print!(" {}", employee.name);
+        }
+        println!();
+    }
 }
 
-fn exercise_3_add_people_department(company: &mut HashMap<String, Vec<String>>) {
-    let mut department = String::new();
-    let mut name = String::new();
-    print!("Department: ");
-    stdout().flush().expect("\x1B[31mERROR\x1B[0m: Failed to flush stdout");
-    get_user_input(&mut department);
-    print!("Name: ");
-    stdout().flush().expect("\x1B[31mERROR\x1B[0m: Failed to flush stdout");
-    get_user_input(&mut name);
-    match company.get_mut(&department) {
-        Some(vector) => {
-            vector.push(name.clone());
-        }
-        None => {
-            eprintln!("\x1B[31mERROR\x1B[0m: Department does not exists inside company.");
+fn exercise_3_list_people_department(directory: &Directory, department: &str) {
+    match directory.department(department) {
+        Some(department) => {
+            let names: Vec<&str> = directory
+                .employees_in(department.id)
+                .map(|employee| employee.name.as_str())
+                .collect();
+            println!("{} = {:?}", department.name, names);
         }
-    };
+        None => print_error(&DirectoryError::NoSuchDepartment(department.to_string())),
+    }
 }
@@ -0,0 +1,207 @@
+//! Pig latin: the leading consonants of each word move to the end, followed
+//! by "ay", so "string" becomes "ing-stray". Words starting with a vowel get
+//! "hay" instead, so "apple" becomes "apple-hay".
+//!
+//! Only words written in the Latin alphabet are translated. Other words,
+//! numbers, whitespace and punctuation are kept as they are.
+
+/// Vowels, with the accented forms the Latin alphabet commonly uses.
+const VOWELS: &str = "aeiouàáâãäåāăąǎèéêëēĕėęěìíîïĩīĭįǐòóôõöøōŏőǒùúûüũūŭůűųǔæœ";
+
+/// Translates every word of `text` into pig latin.
+pub fn encode(text: &str) -> String {
+    translate(text, false, encode_word)
+}
+
+/// Translates pig latin made by [`encode`] back. Words ending in "-hay" are
+/// read as starting with a vowel, so "house" comes back as "ouse".
+pub fn decode(text: &str) -> String {
+    translate(text, true, decode_word)
+}
+
+/// Translates a single word, keeping its capitalization.
+pub fn encode_word(word: &str) -> String {
+    if !word.chars().all(is_latin) {
+        return word.to_string();
+    }
+    with_case(word, |word| {
+        let cluster = leading_consonants(word);
+        let (cluster, rest) = word.split_at(cluster);
+        match (cluster, rest) {
+            ("", _) => format!("{rest}-hay"),
+            // A word without vowels has nothing to move.
+            (_, "") => format!("{cluster}-ay"),
+            _ => format!("{rest}-{cluster}ay"),
+        }
+    })
+}
+
+/// Translates a single pig latin word back, or returns it unchanged if it
+/// isn't pig latin.
+pub fn decode_word(word: &str) -> String {
+    if !word.chars().all(|c| c == '-' || is_latin(c)) {
+        return word.to_string();
+    }
+    with_case(word, |word| {
+        let Some((rest, suffix)) = word.rsplit_once('-') else {
+            return word.to_string();
+        };
+        match suffix.strip_suffix("ay") {
+            Some("h") | Some("") => rest.to_string(),
+            Some(cluster) if leading_consonants(cluster) == cluster.len() => {
+                format!("{cluster}{rest}")
+            }
+            _ => word.to_string(),
+        }
+    })
+}
+
+/// Calls `translate` on the word in lowercase, or with only its first letter
+/// lowercased, and puts the capitalization back on the result.
+fn with_case(word: &str, translate: impl Fn(&str) -> String) -> String {
+    let letters = || word.chars().filter(|c| c.is_alphabetic());
+    let capitalized = letters().next().is_some_and(char::is_uppercase);
+
+    if capitalized && letters().count() > 1 && letters().all(char::is_uppercase) {
+        translate(&word.to_lowercase()).to_uppercase()
+    } else if capitalized {
+        capitalize(&translate(&uncapitalize(word)))
+    } else {
+        translate(word)
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn uncapitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// The length in bytes of the consonants a lowercase word starts with. A "y"
+/// after the first letter counts as a vowel, and a "u" after a "q" stays
+/// with it.
+fn leading_consonants(word: &str) -> usize {
+    let mut end = 0;
+    let mut previous = None;
+    for (i, c) in word.char_indices() {
+        let vowel = is_vowel(c) || (c == 'y' && i > 0);
+        if vowel && !(c == 'u' && previous == Some('q')) {
+            break;
+        }
+        end = i + c.len_utf8();
+        previous = Some(c);
+    }
+    end
+}
+
+pub(crate) fn is_vowel(c: char) -> bool {
+    c.to_lowercase().all(|c| VOWELS.contains(c))
+}
+
+/// Letters of the Latin alphabet, with their accented and extended forms.
+fn is_latin(c: char) -> bool {
+    c.is_ascii_alphabetic()
+        || c == '\''
+        || c == '’'
+        || (c.is_alphabetic() && matches!(c, '\u{C0}'..='\u{24F}' | '\u{1E00}'..='\u{1EFF}'))
+}
+
+/// Applies `word` to each word of `text`. Apostrophes between letters are
+/// part of a word, and so is one hyphen when `hyphenated`.
+fn translate(text: &str, hyphenated: bool, word: impl Fn(&str) -> String) -> String {
+    let mut translated = String::with_capacity(text.len());
+    let mut start = None;
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        let next_is_letter = chars.peek().is_some_and(|&(_, next)| next.is_alphabetic());
+        let joins = match c {
+            '\'' | '’' => true,
+            '-' => hyphenated && start.is_some_and(|from| !text[from..i].contains('-')),
+            _ => false,
+        };
+        let in_word = c.is_alphabetic() || (start.is_some() && joins && next_is_letter);
+
+        match (start, in_word) {
+            (None, true) => start = Some(i),
+            (Some(from), false) => {
+                translated.push_str(&word(&text[from..i]));
+                translated.push(c);
+                start = None;
+            }
+            (None, false) => translated.push(c),
+            (Some(_), true) => {}
+        }
+    }
+    if let Some(from) = start {
+        translated.push_str(&word(&text[from..]));
+    }
+    translated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn consonant_clusters_move() {
+        assert_eq!("ing-stray", encode_word("string"));
+        assert_eq!("irst-fay", encode_word("first"));
+        assert_eq!("een-quay", encode_word("queen"));
+        assert_eq!("ythm-rhay", encode_word("rhythm"));
+        assert_eq!("ellow-yay", encode_word("yellow"));
+        assert_eq!("hmm-ay", encode_word("hmm"));
+    }
+
+    #[test]
+    fn vowels_get_hay() {
+        assert_eq!("apple-hay", encode_word("apple"));
+        assert_eq!("école-hay", encode_word("école"));
+        assert_eq!("über-hay", encode_word("über"));
+        assert_eq!("ølet-hay", encode_word("ølet"));
+    }
+
+    #[test]
+    fn capitalization() {
+        assert_eq!("Ing-stray", encode_word("String"));
+        assert_eq!("ING-STRAY", encode_word("STRING"));
+        assert_eq!("Apple-hay", encode_word("Apple"));
+        assert_eq!("Élan-hay", encode_word("Élan"));
+        assert_eq!("A-hay", encode_word("A"));
+    }
+
+    #[test]
+    fn punctuation_and_other_text_are_kept() {
+        assert_eq!(
+            "Ello-hay, orld-way! On't-day (ee-say: 42)",
+            encode("Hello, world! Don't (see: 42)")
+        );
+        assert_eq!("ell-way-own-knay", encode("well-known"));
+        assert_eq!("Привет, orld-way", encode("Привет, world"));
+        assert_eq!("東京 ity-cay", encode("東京 city"));
+        assert_eq!("  'ote-quay'\n", encode("  'quote'\n"));
+    }
+
+    #[test]
+    fn decoding() {
+        let text = "The QUICK brown fox, doesn't it? Straße, école, rhythm; well-known 東京";
+        assert_eq!(text, decode(&encode(text)));
+        assert_eq!("string", decode_word("ing-stray"));
+        assert_eq!("Apple", decode_word("Apple-hay"));
+
+        // Not pig latin, so left alone.
+        assert_eq!("hello", decode_word("hello"));
+        assert_eq!("up-to", decode("up-to"));
+        assert_eq!("ab-cd-eay", decode_word("ab-cd-eay"));
+    }
+}
@@ -0,0 +1,311 @@
+//! Importing and exporting the directory as CSV. Files have a `name` and a
+//! `department` column; any other columns are kept as employee details.
+
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::io;
+
+use crate::directory::{self, Change, Directory};
+
+/// Why a row of an imported file was left out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Problem {
+    MissingName,
+    MissingDepartment,
+    /// The same name is on an earlier line of the file.
+    Duplicate {
+        name: String,
+        first_line: u64,
+    },
+    /// The employee is already in the directory, in that department.
+    AlreadyThere {
+        name: String,
+    },
+    /// The employee is already in the directory, in another department.
+    Conflict {
+        name: String,
+        department: String,
+        wanted: String,
+    },
+}
+
+/// A row left out of an import.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Issue {
+    pub line: u64,
+    pub problem: Problem,
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+        match &self.problem {
+            Problem::MissingName => write!(f, "no name"),
+            Problem::MissingDepartment => write!(f, "no department"),
+            Problem::Duplicate { name, first_line } => {
+                write!(f, "'{name}' is already on line {first_line}")
+            }
+            Problem::AlreadyThere { name } => write!(f, "'{name}' is already in the directory"),
+            Problem::Conflict {
+                name,
+                department,
+                wanted,
+            } => write!(f, "'{name}' works in {department}, not {wanted}"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ImportError {
+    Csv(csv::Error),
+    MissingColumn(&'static str),
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ImportError::Csv(e) => write!(f, "{e}"),
+            ImportError::MissingColumn(column) => write!(f, "there is no '{column}' column"),
+        }
+    }
+}
+
+impl std::error::Error for ImportError {}
+
+impl From<csv::Error> for ImportError {
+    fn from(e: csv::Error) -> ImportError {
+        ImportError::Csv(e)
+    }
+}
+
+/// What importing a file would do: the employees to add, and the rows that
+/// were left out.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Import {
+    pub changes: Vec<Change>,
+    pub issues: Vec<Issue>,
+}
+
+/// Reads `input` and works out how it would change `directory`, which is
+/// left as it is. Rows that duplicate or conflict with others are reported
+/// rather than merged.
+pub fn plan_import(directory: &Directory, input: impl io::Read) -> Result<Import, ImportError> {
+    let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(input);
+    let headers: Vec<String> = reader
+        .headers()?
+        .iter()
+        .map(|header| header.trim().to_string())
+        .collect();
+    let column = |name: &'static str| {
+        headers
+            .iter()
+            .position(|header| header.eq_ignore_ascii_case(name))
+            .ok_or(ImportError::MissingColumn(name))
+    };
+    let (name_column, department_column) = (column("name")?, column("department")?);
+
+    let mut import = Import::default();
+    let mut seen = HashMap::new();
+    for record in reader.records() {
+        let record = record?;
+        let line = record.position().map_or(0, |position| position.line());
+        let field = |index| record.get(index).unwrap_or("").trim();
+        let (name, department) = (field(name_column), field(department_column));
+
+        let problem = if name.is_empty() {
+            Some(Problem::MissingName)
+        } else if department.is_empty() {
+            Some(Problem::MissingDepartment)
+        } else if let Some(&first_line) = seen.get(&directory::key(name)) {
+            Some(Problem::Duplicate {
+                name: name.to_string(),
+                first_line,
+            })
+        } else if let Some(existing) = directory.employee(name) {
+            let current = directory.department_name(existing);
+            if directory::key(&current) == directory::key(department) {
+                Some(Problem::AlreadyThere {
+                    name: existing.name.clone(),
+                })
+            } else {
+                Some(Problem::Conflict {
+                    name: existing.name.clone(),
+                    department: current,
+                    wanted: department.to_string(),
+                })
+            }
+        } else {
+            None
+        };
+
+        if let Some(problem) = problem {
+            import.issues.push(Issue { line, problem });
+            continue;
+        }
+        seen.insert(directory::key(name), line);
+
+        let details = headers
+            .iter()
+            .enumerate()
+            .filter(|&(index, header)| {
+                index != name_column && index != department_column && !header.is_empty()
+            })
+            .map(|(index, header)| (header.clone(), field(index).to_string()))
+            .filter(|(_, value)| !value.is_empty())
+            .collect();
+        import.changes.push(Change::Add {
+            name: name.to_string(),
+            department: department.to_string(),
+            details,
+        });
+    }
+    Ok(import)
+}
+
+/// Writes every employee as CSV, sorted by department and then by name.
+/// Details become extra columns after the name and department.
+pub fn export(directory: &Directory, output: impl io::Write) -> csv::Result<()> {
+    let departments: Vec<_> = directory.departments().collect();
+    let employees: Vec<_> = departments
+        .iter()
+        .flat_map(|department| directory.employees_in(department.id))
+        .collect();
+    let columns: BTreeSet<&str> = employees
+        .iter()
+        .flat_map(|employee| employee.details.keys().map(String::as_str))
+        .collect();
+
+    let mut writer = csv::Writer::from_writer(output);
+    writer.write_record(
+        ["name", "department"]
+            .into_iter()
+            .chain(columns.iter().copied()),
+    )?;
+    for employee in employees {
+        writer.write_record(
+            [
+                employee.name.as_str(),
+                directory.department_name(employee).as_str(),
+            ]
+            .into_iter()
+            .chain(
+                columns
+                    .iter()
+                    .map(|&column| employee.details.get(column).map_or("", String::as_str)),
+            ),
+        )?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn directory() -> Directory {
+        let mut directory = Directory::new();
+        directory.hire("Sally", "Engineering").unwrap();
+        directory
+    }
+
+    #[test]
+    fn import_reports_problems() {
+        let csv = "\
+Name,Department,Email
+Amir,Sales,amir@example.com
+sally,engineering,
+Sally,Sales,
+AMIR,Legal,
+,Sales,
+Bo, ,
+Ann,\"R&D, Labs\"
+";
+        let import = plan_import(&directory(), csv.as_bytes()).unwrap();
+
+        assert_eq!(
+            vec![
+                Change::Add {
+                    name: String::from("Amir"),
+                    department: String::from("Sales"),
+                    details: BTreeMap::from([(
+                        String::from("Email"),
+                        String::from("amir@example.com")
+                    )]),
+                },
+                Change::Add {
+                    name: String::from("Ann"),
+                    department: String::from("R&D, Labs"),
+                    details: BTreeMap::new(),
+                },
+            ],
+            import.changes
+        );
+        let issues: Vec<String> = import.issues.iter().map(Issue::to_string).collect();
+        assert_eq!(
+            vec![
+                "line 3: 'Sally' is already in the directory",
+                "line 4: 'Sally' works in Engineering, not Sales",
+                "line 5: 'AMIR' is already on line 2",
+                "line 6: no name",
+                "line 7: no department",
+            ],
+            issues
+        );
+    }
+
+    #[test]
+    fn import_needs_both_columns() {
+        let error = plan_import(&directory(), "name,team\nAmir,Sales\n".as_bytes()).unwrap_err();
+        assert_eq!("there is no 'department' column", error.to_string());
+    }
+
+    #[test]
+    fn export_is_sorted() {
+        let mut directory = directory();
+        directory.hire("amir", "Sales").unwrap();
+        directory.hire("Bo", "Engineering").unwrap();
+        directory
+            .hire_with_details(
+                "Ann",
+                "engineering",
+                BTreeMap::from([(String::from("Phone"), String::from("555, ext 2"))]),
+            )
+            .unwrap();
+
+        let mut output = Vec::new();
+        export(&directory, &mut output).unwrap();
+        assert_eq!(
+            "name,department,Phone\n\
+             Ann,Engineering,\"555, ext 2\"\n\
+             Bo,Engineering,\n\
+             Sally,Engineering,\n\
+             amir,Sales,\n",
+            String::from_utf8(output).unwrap()
+        );
+    }
+
+    #[test]
+    fn export_then_import() {
+        let mut directory = directory();
+        directory.hire("Amir", "Sales").unwrap();
+        let mut output = Vec::new();
+        export(&directory, &mut output).unwrap();
+
+        let import = plan_import(&Directory::new(), output.as_slice()).unwrap();
+        let mut imported = Directory::new();
+        for change in &import.changes {
+            change.apply(&mut imported).unwrap();
+        }
+        assert!(import.issues.is_empty());
+        assert_eq!(2, imported.departments().count());
+        assert_eq!(
+            "Sales",
+            imported
+                .department_of(imported.employee("amir").unwrap())
+                .unwrap()
+                .name
+        );
+    }
+}
@@ -0,0 +1,246 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+/// Why a statistic can't be computed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StatsError {
+    Empty,
+    /// Quantiles must be between 0 and 1.
+    InvalidQuantile(f64),
+}
+
+impl fmt::Display for StatsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StatsError::Empty => write!(f, "no values"),
+            StatsError::InvalidQuantile(q) => {
+                write!(f, "quantile {q} is not between 0 and 1")
+            }
+        }
+    }
+}
+
+impl std::error::Error for StatsError {}
+
+/// The arithmetic mean, or `None` for no values.
+pub fn mean<T: Copy + Into<f64>>(values: &[T]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    let sum: f64 = values.iter().map(|&value| value.into()).sum();
+    Some(sum / values.len() as f64)
+}
+
+/// The middle value once sorted, or the mean of the two middle values when
+/// there is an even number of them.
+pub fn median<T: Copy + Into<f64>>(values: &[T]) -> Option<f64> {
+    quantile(values, 0.5).ok()
+}
+
+/// Every value that occurs most often, in ascending order. All of them are
+/// returned when there is a tie.
+pub fn modes<T: Copy + Into<f64>>(values: &[T]) -> Option<Vec<T>> {
+    let sorted = sorted(values);
+    let mut modes = Vec::new();
+    let mut best = 0;
+
+    for run in sorted.chunk_by(|&a, &b| a.into() == b.into()) {
+        match run.len().cmp(&best) {
+            Ordering::Greater => {
+                best = run.len();
+                modes.clear();
+                modes.push(run[0]);
+            }
+            Ordering::Equal => modes.push(run[0]),
+            Ordering::Less => {}
+        }
+    }
+
+    if modes.is_empty() {
+        None
+    } else {
+        Some(modes)
+    }
+}
+
+/// The population variance: the mean squared distance from the mean.
+pub fn variance<T: Copy + Into<f64>>(values: &[T]) -> Option<f64> {
+    let mean = mean(values)?;
+    Some(squared_deviations(values, mean) / values.len() as f64)
+}
+
+/// The sample variance, dividing by `n - 1`. Needs at least two values.
+pub fn sample_variance<T: Copy + Into<f64>>(values: &[T]) -> Option<f64> {
+    if values.len() < 2 {
+        return None;
+    }
+    let mean = mean(values)?;
+    Some(squared_deviations(values, mean) / (values.len() - 1) as f64)
+}
+
+/// The population standard deviation.
+pub fn std_dev<T: Copy + Into<f64>>(values: &[T]) -> Option<f64> {
+    variance(values).map(f64::sqrt)
+}
+
+/// The sample standard deviation. Needs at least two values.
+pub fn sample_std_dev<T: Copy + Into<f64>>(values: &[T]) -> Option<f64> {
+    sample_variance(values).map(f64::sqrt)
+}
+
+/// The value below which a `q` share of the values fall, interpolating
+/// linearly between the two closest ranks.
+pub fn quantile<T: Copy + Into<f64>>(values: &[T], q: f64) -> Result<f64, StatsError> {
+    if !(0.0..=1.0).contains(&q) {
+        return Err(StatsError::InvalidQuantile(q));
+    }
+    if values.is_empty() {
+        return Err(StatsError::Empty);
+    }
+
+    let sorted: Vec<f64> = sorted(values).into_iter().map(Into::into).collect();
+    let rank = q * (sorted.len() - 1) as f64;
+    let below = rank.floor() as usize;
+    let above = rank.ceil() as usize;
+    let fraction = rank - below as f64;
+    Ok(sorted[below] + (sorted[above] - sorted[below]) * fraction)
+}
+
+fn sorted<T: Copy + Into<f64>>(values: &[T]) -> Vec<T> {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|&a, &b| a.into().total_cmp(&b.into()));
+    sorted
+}
+
+fn squared_deviations<T: Copy + Into<f64>>(values: &[T], mean: f64) -> f64 {
+    values
+        .iter()
+        .map(|&value| (value.into() - mean).powi(2))
+        .sum()
+}
+
+/// The usual statistics of a data set, all computed at once.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summary {
+    pub count: usize,
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub median: f64,
+    pub modes: Vec<f64>,
+    pub variance: f64,
+    pub std_dev: f64,
+    /// The first and third quartiles.
+    pub quartiles: (f64, f64),
+}
+
+impl Summary {
+    /// Summarizes `values`, or returns `None` if there are none.
+    pub fn of<T: Copy + Into<f64>>(values: &[T]) -> Option<Summary> {
+        let sorted: Vec<f64> = sorted(values).into_iter().map(Into::into).collect();
+        let variance = variance(&sorted)?;
+
+        Some(Summary {
+            count: sorted.len(),
+            min: sorted[0],
+            max: sorted[sorted.len() - 1],
+            mean: mean(&sorted)?,
+            median: median(&sorted)?,
+            modes: modes(&sorted)?,
+            variance,
+            std_dev: variance.sqrt(),
+            quartiles: (quantile(&sorted, 0.25).ok()?, quantile(&sorted, 0.75).ok()?),
+        })
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Count: {}", self.count)?;
+        writeln!(f, "Min: {}", self.min)?;
+        writeln!(f, "Max: {}", self.max)?;
+        writeln!(f, "Mean: {}", self.mean)?;
+        writeln!(f, "Median: {}", self.median)?;
+        writeln!(f, "Modes: {:?}", self.modes)?;
+        writeln!(f, "Variance: {}", self.variance)?;
+        writeln!(f, "Std dev: {}", self.std_dev)?;
+        writeln!(f, "Quartiles: {} {}", self.quartiles.0, self.quartiles.1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NONE: [i32; 0] = [];
+
+    #[test]
+    fn mean_is_not_truncated() {
+        assert_eq!(Some(2.5), mean(&[1, 2, 3, 4]));
+        assert_eq!(Some(-0.5), mean(&[-1.0, 0.0]));
+        assert_eq!(None, mean(&NONE));
+    }
+
+    #[test]
+    fn median_of_odd_and_even_lengths() {
+        assert_eq!(Some(3.0), median(&[5, 1, 3]));
+        assert_eq!(Some(2.5), median(&[4, 1, 3, 2]));
+        assert_eq!(Some(7.0), median(&[7u8]));
+        assert_eq!(None, median(&NONE));
+    }
+
+    #[test]
+    fn modes_keep_ties() {
+        assert_eq!(Some(vec![5]), modes(&[1, 5, 2, 5, 3]));
+        assert_eq!(Some(vec![1, 4]), modes(&[4, 1, 4, 2, 1]));
+        assert_eq!(Some(vec![1.5, 2.5]), modes(&[2.5, 1.5]));
+        assert_eq!(None, modes(&NONE));
+    }
+
+    #[test]
+    fn spread() {
+        let values = [2, 4, 4, 4, 5, 5, 7, 9];
+        assert_eq!(Some(4.0), variance(&values));
+        assert_eq!(Some(2.0), std_dev(&values));
+        assert_eq!(Some(32.0 / 7.0), sample_variance(&values));
+        assert_eq!(Some((32.0f64 / 7.0).sqrt()), sample_std_dev(&values));
+
+        assert_eq!(Some(0.0), variance(&[3]));
+        assert_eq!(None, sample_variance(&[3]));
+        assert_eq!(None, std_dev(&NONE));
+    }
+
+    #[test]
+    fn quantiles_interpolate() {
+        let values = [1, 2, 3, 4, 5];
+        assert_eq!(Ok(1.0), quantile(&values, 0.0));
+        assert_eq!(Ok(2.0), quantile(&values, 0.25));
+        assert_eq!(Ok(5.0), quantile(&values, 1.0));
+        assert_eq!(Ok(3.5), quantile(&[4, 3], 0.5));
+        assert_eq!(Ok(3.25), quantile(&[3, 4], 0.25));
+
+        assert_eq!(Err(StatsError::Empty), quantile(&NONE, 0.5));
+        assert_eq!(
+            Err(StatsError::InvalidQuantile(1.5)),
+            quantile(&values, 1.5)
+        );
+        assert!(quantile(&values, f64::NAN).is_err());
+    }
+
+    #[test]
+    fn summary() {
+        let summary = Summary::of(&[3, 1, 2, 2]).unwrap();
+        assert_eq!(4, summary.count);
+        assert_eq!((1.0, 3.0), (summary.min, summary.max));
+        assert_eq!(2.0, summary.mean);
+        assert_eq!(2.0, summary.median);
+        assert_eq!(vec![2.0], summary.modes);
+        assert_eq!(0.5, summary.variance);
+        assert_eq!((1.75, 2.25), summary.quartiles);
+        assert!(summary
+            .to_string()
+            .starts_with("Count: 4\nMin: 1\nMax: 3\n"));
+
+        assert_eq!(None, Summary::of(&NONE));
+    }
+}
@@ -0,0 +1,459 @@
+//! The `stats` command: summarizes numbers read from files or stdin.
+
+use std::error::Error;
+use std::fmt::{self, Write as _};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Write};
+
+use crate::statistics::Summary;
+
+const BAR_WIDTH: usize = 40;
+
+/// Which column of a CSV file holds the numbers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Column {
+    /// Counted from 1, for files without a header.
+    Index(usize),
+    /// Looked up in the header line.
+    Name(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Layout {
+    /// Any number of values per line, separated by whitespace.
+    Whitespace,
+    Csv(Column),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Json,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    /// Files to read; stdin when empty or `-`.
+    pub paths: Vec<String>,
+    pub layout: Layout,
+    pub format: Format,
+    pub histogram: Option<usize>,
+}
+
+impl Config {
+    pub fn build(mut args: impl Iterator<Item = String>) -> Result<Config, &'static str> {
+        args.next();
+
+        let mut paths = Vec::new();
+        let mut layout = Layout::Whitespace;
+        let mut format = Format::Text;
+        let mut histogram = None;
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--column" => match args.next() {
+                    Some(column) => {
+                        layout = Layout::Csv(match column.parse() {
+                            Ok(0) => return Err("Columns are counted from 1"),
+                            Ok(index) => Column::Index(index),
+                            Err(_) => Column::Name(column),
+                        })
+                    }
+                    None => return Err("Didn't get a column"),
+                },
+                "--format" => match args.next().as_deref() {
+                    Some("text") => format = Format::Text,
+                    Some("json") => format = Format::Json,
+                    _ => return Err("Format must be text or json"),
+                },
+                "--histogram" => match args.next().and_then(|bins| bins.parse().ok()) {
+                    Some(bins) if bins > 0 => histogram = Some(bins),
+                    _ => return Err("Histogram needs a positive number of bins"),
+                },
+                _ if arg.starts_with("--") => return Err("Unknown argument"),
+                _ => paths.push(arg),
+            }
+        }
+
+        Ok(Config {
+            paths,
+            layout,
+            format,
+            histogram,
+        })
+    }
+}
+
+/// A line that couldn't be read as a number.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidLine {
+    pub source: String,
+    pub line_number: usize,
+    pub reason: String,
+}
+
+impl fmt::Display for InvalidLine {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.source, self.line_number, self.reason)
+    }
+}
+
+/// The numbers read from every input, and the lines that were skipped.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Numbers {
+    pub values: Vec<f64>,
+    pub invalid: Vec<InvalidLine>,
+}
+
+impl Numbers {
+    /// Reads `input` laid out as `layout`. Blank lines are skipped; bad
+    /// lines are recorded in `invalid` and reading carries on.
+    pub fn read(&mut self, source: &str, input: impl BufRead, layout: &Layout) -> io::Result<()> {
+        let mut column = match layout {
+            Layout::Csv(Column::Index(index)) => Some(index - 1),
+            _ => None,
+        };
+
+        for (index, line) in input.lines().enumerate() {
+            let line = line?;
+            let line_number = index + 1;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let result = match layout {
+                Layout::Whitespace => self.read_words(&line),
+                Layout::Csv(Column::Name(name)) if column.is_none() => {
+                    match split_csv(&line).iter().position(|field| field == name) {
+                        Some(position) => {
+                            column = Some(position);
+                            Ok(())
+                        }
+                        None => Err(format!("no column named '{name}' in the header")),
+                    }
+                }
+                Layout::Csv(_) => {
+                    let fields = split_csv(&line);
+                    let index = column.expect("the column is known after the header");
+                    match fields.get(index) {
+                        Some(field) => parse_number(field).map(|value| self.values.push(value)),
+                        None => Err(format!("no column {}", index + 1)),
+                    }
+                }
+            };
+
+            if let Err(reason) = result {
+                self.invalid.push(InvalidLine {
+                    source: source.to_string(),
+                    line_number,
+                    reason,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Keeps the line's numbers only if every word on it is one.
+    fn read_words(&mut self, line: &str) -> Result<(), String> {
+        let values = line
+            .split_whitespace()
+            .map(parse_number)
+            .collect::<Result<Vec<_>, _>>()?;
+        self.values.extend(values);
+        Ok(())
+    }
+}
+
+fn parse_number(text: &str) -> Result<f64, String> {
+    let text = text.trim();
+    match text.parse::<f64>() {
+        Ok(value) if value.is_finite() => Ok(value),
+        _ => Err(format!("'{text}' is not a number")),
+    }
+}
+
+/// Splits a CSV line on commas, honouring double-quoted fields.
+fn split_csv(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field).trim().to_string()),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field.trim().to_string());
+    fields
+}
+
+/// Counts of values in equal-width bins between the smallest and largest.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram {
+    pub min: f64,
+    pub width: f64,
+    pub counts: Vec<usize>,
+}
+
+impl Histogram {
+    /// Returns `None` for no values or no bins.
+    pub fn new(values: &[f64], bins: usize) -> Option<Histogram> {
+        if bins == 0 {
+            return None;
+        }
+        let min = values.iter().copied().reduce(f64::min)?;
+        let max = values.iter().copied().reduce(f64::max)?;
+        let width = if max > min {
+            (max - min) / bins as f64
+        } else {
+            1.0
+        };
+
+        let mut counts = vec![0; bins];
+        for value in values {
+            // The largest value belongs in the last bin, not past it.
+            let bin = (((value - min) / width) as usize).min(bins - 1);
+            counts[bin] += 1;
+        }
+
+        Some(Histogram { min, width, counts })
+    }
+
+    /// The start and end of each bin.
+    pub fn bounds(&self) -> impl Iterator<Item = (f64, f64)> + '_ {
+        (0..self.counts.len()).map(|bin| {
+            let start = self.min + self.width * bin as f64;
+            (start, start + self.width)
+        })
+    }
+}
+
+impl fmt::Display for Histogram {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let most = self.counts.iter().copied().max().unwrap_or(0).max(1);
+        for ((start, end), &count) in self.bounds().zip(&self.counts) {
+            let bar = "#".repeat(count * BAR_WIDTH / most);
+            writeln!(f, "[{start:.2}, {end:.2}) {bar} {count}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Renders the summary as a JSON object.
+pub fn to_json(summary: &Summary, histogram: Option<&Histogram>, invalid: usize) -> String {
+    let numbers = |values: &[f64]| {
+        let values: Vec<String> = values.iter().map(f64::to_string).collect();
+        format!("[{}]", values.join(", "))
+    };
+
+    let mut json = String::from("{\n");
+    let _ = writeln!(json, "  \"count\": {},", summary.count);
+    let _ = writeln!(json, "  \"min\": {},", summary.min);
+    let _ = writeln!(json, "  \"max\": {},", summary.max);
+    let _ = writeln!(json, "  \"mean\": {},", summary.mean);
+    let _ = writeln!(json, "  \"median\": {},", summary.median);
+    let _ = writeln!(json, "  \"modes\": {},", numbers(&summary.modes));
+    let _ = writeln!(json, "  \"variance\": {},", summary.variance);
+    let _ = writeln!(json, "  \"std_dev\": {},", summary.std_dev);
+    let (q1, q3) = summary.quartiles;
+    let _ = writeln!(json, "  \"quartiles\": {},", numbers(&[q1, q3]));
+    if let Some(histogram) = histogram {
+        let bins: Vec<String> = histogram
+            .bounds()
+            .zip(&histogram.counts)
+            .map(|((start, end), count)| {
+                format!("{{\"start\": {start}, \"end\": {end}, \"count\": {count}}}")
+            })
+            .collect();
+        let _ = writeln!(json, "  \"histogram\": [{}],", bins.join(", "));
+    }
+    let _ = writeln!(json, "  \"invalid_lines\": {invalid}");
+    json.push_str("}\n");
+    json
+}
+
+/// Reads every input, reports invalid lines on `errors` and writes the
+/// summary to `output`.
+pub fn run_to(
+    config: &Config,
+    output: &mut impl Write,
+    errors: &mut impl Write,
+) -> Result<(), Box<dyn Error>> {
+    let mut numbers = Numbers::default();
+
+    if config.paths.is_empty() {
+        numbers.read("<stdin>", io::stdin().lock(), &config.layout)?;
+    }
+    for path in &config.paths {
+        if path == "-" {
+            numbers.read("<stdin>", io::stdin().lock(), &config.layout)?;
+        } else {
+            let file = File::open(path).map_err(|err| format!("{path}: {err}"))?;
+            numbers.read(path, BufReader::new(file), &config.layout)?;
+        }
+    }
+
+    for invalid in &numbers.invalid {
+        writeln!(errors, "{invalid}")?;
+    }
+
+    let summary = Summary::of(&numbers.values).ok_or("no numbers to summarize")?;
+    let histogram = config
+        .histogram
+        .and_then(|bins| Histogram::new(&numbers.values, bins));
+
+    match config.format {
+        Format::Text => {
+            write!(output, "{summary}")?;
+            if let Some(histogram) = &histogram {
+                write!(output, "Histogram:\n{histogram}")?;
+            }
+            if !numbers.invalid.is_empty() {
+                writeln!(output, "Invalid lines: {}", numbers.invalid.len())?;
+            }
+        }
+        Format::Json => write!(
+            output,
+            "{}",
+            to_json(&summary, histogram.as_ref(), numbers.invalid.len())
+        )?,
+    }
+
+    Ok(())
+}
+
+pub fn run(config: Config) -> Result<(), Box<dyn Error>> {
+    run_to(&config, &mut io::stdout(), &mut io::stderr())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(args: &[&str]) -> Result<Config, &'static str> {
+        let args = ["stats"].iter().chain(args).map(|arg| arg.to_string());
+        Config::build(args)
+    }
+
+    fn read(contents: &str, layout: &Layout) -> Numbers {
+        let mut numbers = Numbers::default();
+        numbers.read("test", contents.as_bytes(), layout).unwrap();
+        numbers
+    }
+
+    #[test]
+    fn arguments() {
+        let json = config(&["a.txt", "--column", "price", "--format", "json"]).unwrap();
+        assert_eq!(vec!["a.txt"], json.paths);
+        assert_eq!(
+            Layout::Csv(Column::Name(String::from("price"))),
+            json.layout
+        );
+        assert_eq!(Format::Json, json.format);
+
+        let indexed = config(&["--column", "2", "--histogram", "5"]).unwrap();
+        assert_eq!(Layout::Csv(Column::Index(2)), indexed.layout);
+        assert_eq!(Some(5), indexed.histogram);
+
+        assert!(config(&["--column", "0"]).is_err());
+        assert!(config(&["--histogram", "0"]).is_err());
+        assert!(config(&["--format", "xml"]).is_err());
+        assert!(config(&["--verbose"]).is_err());
+    }
+
+    #[test]
+    fn whitespace_separated() {
+        let numbers = read("1 2\n\n  3.5\t4\nfive\n6 x 7\n-1e2\n", &Layout::Whitespace);
+
+        assert_eq!(vec![1.0, 2.0, 3.5, 4.0, -100.0], numbers.values);
+        let invalid: Vec<String> = numbers
+            .invalid
+            .iter()
+            .map(|line| line.to_string())
+            .collect();
+        assert_eq!(
+            vec![
+                "test:4: 'five' is not a number",
+                "test:5: 'x' is not a number"
+            ],
+            invalid
+        );
+    }
+
+    #[test]
+    fn not_finite_is_invalid() {
+        let numbers = read("NaN\ninf\n1\n", &Layout::Whitespace);
+        assert_eq!(vec![1.0], numbers.values);
+        assert_eq!(2, numbers.invalid.len());
+    }
+
+    #[test]
+    fn csv_by_name_and_index() {
+        let csv = "name,\"price, in $\"\napple,1.5\n\"pear, green\",2\nplum,n/a\nfig\n";
+
+        let numbers = read(csv, &Layout::Csv(Column::Name(String::from("price, in $"))));
+        assert_eq!(vec![1.5, 2.0], numbers.values);
+        let lines: Vec<usize> = numbers
+            .invalid
+            .iter()
+            .map(|line| line.line_number)
+            .collect();
+        assert_eq!(vec![4, 5], lines);
+
+        let numbers = read("1,10\n2,20\n", &Layout::Csv(Column::Index(2)));
+        assert_eq!(vec![10.0, 20.0], numbers.values);
+
+        let numbers = read(csv, &Layout::Csv(Column::Name(String::from("cost"))));
+        assert!(numbers.values.is_empty());
+        assert_eq!(
+            "no column named 'cost' in the header",
+            numbers.invalid[0].reason
+        );
+    }
+
+    #[test]
+    fn split_quoted_fields() {
+        assert_eq!(
+            vec!["a", "b, c", "say \"hi\""],
+            split_csv("a, \"b, c\",\"say \"\"hi\"\"\"")
+        );
+        assert_eq!(vec!["", ""], split_csv(","));
+    }
+
+    #[test]
+    fn histogram() {
+        let histogram = Histogram::new(&[1.0, 2.0, 2.5, 3.0, 5.0], 2).unwrap();
+        assert_eq!(2.0, histogram.width);
+        assert_eq!(vec![3, 2], histogram.counts);
+        assert_eq!(
+            "[1.00, 3.00) ######################################## 3\n\
+             [3.00, 5.00) ########################## 2\n",
+            histogram.to_string()
+        );
+
+        let same = Histogram::new(&[4.0, 4.0], 3).unwrap();
+        assert_eq!(vec![2, 0, 0], same.counts);
+        assert_eq!(None, Histogram::new(&[], 3));
+    }
+
+    #[test]
+    fn json() {
+        let summary = Summary::of(&[1.0, 2.0, 2.0, 3.0]).unwrap();
+        let histogram = Histogram::new(&[1.0, 3.0], 1).unwrap();
+        let json = to_json(&summary, Some(&histogram), 2);
+
+        assert!(json.starts_with("{\n  \"count\": 4,\n  \"min\": 1,\n"));
+        assert!(json.contains("  \"modes\": [2],\n"));
+        assert!(json.contains("  \"quartiles\": [1.75, 2.25],\n"));
+        assert!(json.contains("  \"histogram\": [{\"start\": 1, \"end\": 3, \"count\": 2}],\n"));
+        assert!(json.ends_with("  \"invalid_lines\": 2\n}\n"));
+    }
+}
@@ -0,0 +1,307 @@
+//! Keeping the company directory between runs, either as one JSON file that
+//! is rewritten on every change or as an append-only log of changes.
+
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::directory::{Change, Directory};
+
+pub trait Storage {
+    /// Reads the saved directory, which is empty if nothing was saved yet.
+    fn load(&mut self) -> io::Result<Directory>;
+
+    /// Saves `changes`, which have already been applied to `directory`.
+    fn save(&mut self, directory: &Directory, changes: &[Change]) -> io::Result<()>;
+}
+
+/// Picks the storage for `path` by its extension: `.json` files hold the
+/// whole directory, anything else is a log.
+pub fn open(path: impl Into<PathBuf>) -> Box<dyn Storage> {
+    let path = path.into();
+    if path
+        .extension()
+        .is_some_and(|extension| extension == "json")
+    {
+        Box::new(JsonFile::new(path))
+    } else {
+        Box::new(LogFile::new(path))
+    }
+}
+
+/// The whole directory as one JSON object, rewritten on every change.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonFile {
+    path: PathBuf,
+}
+
+impl JsonFile {
+    pub fn new(path: impl Into<PathBuf>) -> JsonFile {
+        JsonFile { path: path.into() }
+    }
+}
+
+impl Storage for JsonFile {
+    fn load(&mut self) -> io::Result<Directory> {
+        match fs::read(&self.path) {
+            Ok(bytes) => Ok(serde_json::from_slice(&bytes)?),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Directory::new()),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn save(&mut self, directory: &Directory, _changes: &[Change]) -> io::Result<()> {
+        let mut json = serde_json::to_vec_pretty(directory)?;
+        json.push(b'\n');
+        replace(&self.path, &json)
+    }
+}
+
+/// A line of the log: the changes from one save, or the whole directory,
+/// which is what compaction leaves behind.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+enum Entry {
+    Snapshot { directory: Directory },
+    Changes(Vec<Change>),
+}
+
+/// Changes appended as one JSON line per save, so the changes saved together
+/// are kept or lost together. Once the log has grown to several times the
+/// size of the directory it is compacted into a single snapshot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogFile {
+    path: PathBuf,
+    /// Entries in the file, counted by `load` and `save`.
+    entries: usize,
+}
+
+impl LogFile {
+    /// Compacting before this many entries wouldn't save much.
+    const MIN_ENTRIES: usize = 64;
+
+    pub fn new(path: impl Into<PathBuf>) -> LogFile {
+        LogFile {
+            path: path.into(),
+            entries: 0,
+        }
+    }
+
+    /// Rewrites the log as a single snapshot of `directory`.
+    pub fn compact(&mut self, directory: &Directory) -> io::Result<()> {
+        let snapshot = Entry::Snapshot {
+            directory: directory.clone(),
+        };
+        let mut line = serde_json::to_vec(&snapshot)?;
+        line.push(b'\n');
+        replace(&self.path, &line)?;
+        self.entries = 1;
+        Ok(())
+    }
+
+    fn needs_compacting(&self, directory: &Directory) -> bool {
+        // A snapshot costs about as much as one entry per department and
+        // employee, so compact once the log is twice that long.
+        let snapshot = directory.department_count() + directory.employee_count();
+        self.entries >= Self::MIN_ENTRIES.max(2 * snapshot)
+    }
+}
+
+impl Storage for LogFile {
+    fn load(&mut self) -> io::Result<Directory> {
+        let bytes = match fs::read(&self.path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                self.entries = 0;
+                return Ok(Directory::new());
+            }
+            Err(e) => return Err(e),
+        };
+
+        // A crash while appending can leave the last line unfinished. That
+        // change never finished saving, so it is cut off.
+        let complete = bytes.iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1);
+        if complete < bytes.len() {
+            OpenOptions::new()
+                .write(true)
+                .open(&self.path)?
+                .set_len(complete as u64)?;
+        }
+
+        let mut directory = Directory::new();
+        self.entries = 0;
+        for (index, line) in bytes[..complete].split(|&b| b == b'\n').enumerate() {
+            if line.is_empty() {
+                continue;
+            }
+            let invalid = |e: &dyn fmt::Display| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("{}:{}: {e}", self.path.display(), index + 1),
+                )
+            };
+            match serde_json::from_slice(line).map_err(|e| invalid(&e))? {
+                Entry::Snapshot {
+                    directory: snapshot,
+                } => directory = snapshot,
+                Entry::Changes(changes) => {
+                    for change in &changes {
+                        change.apply(&mut directory).map_err(|e| invalid(&e))?;
+                    }
+                }
+            }
+            self.entries += 1;
+        }
+        Ok(directory)
+    }
+
+    fn save(&mut self, directory: &Directory, changes: &[Change]) -> io::Result<()> {
+        if changes.is_empty() {
+            return Ok(());
+        }
+        if self.needs_compacting(directory) {
+            return self.compact(directory);
+        }
+
+        let mut line = serde_json::to_vec(&Entry::Changes(changes.to_vec()))?;
+        line.push(b'\n');
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        file.write_all(&line)?;
+        file.sync_data()?;
+        self.entries += 1;
+        Ok(())
+    }
+}
+
+/// Writes to a temporary file next to `path` and renames it into place, so a
+/// crash never leaves a half-written file behind.
+fn replace(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let mut temporary = PathBuf::from(path);
+    temporary.as_mut_os_string().push(".tmp");
+
+    let mut file = File::create(&temporary)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+
+    fs::rename(&temporary, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+    use std::env;
+
+    fn temp_path(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("exercises-storage-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    fn add(name: &str, department: &str) -> Change {
+        Change::Add {
+            name: name.to_string(),
+            department: department.to_string(),
+            details: BTreeMap::new(),
+        }
+    }
+
+    /// Applies `changes` to `directory` and saves them.
+    fn change(storage: &mut dyn Storage, directory: &mut Directory, changes: &[Change]) {
+        for change in changes {
+            change.apply(directory).unwrap();
+        }
+        storage.save(directory, changes).unwrap();
+    }
+
+    #[test]
+    fn both_storages_round_trip() {
+        for path in [temp_path("company.json"), temp_path("company.log")] {
+            let mut directory = Directory::new();
+            let mut storage = open(&path);
+            assert_eq!(Directory::new(), storage.load().unwrap());
+
+            change(
+                &mut *storage,
+                &mut directory,
+                &[add("Sally", "Engineering")],
+            );
+            let moved = [
+                Change::Move {
+                    name: String::from("Sally"),
+                    department: String::from("Sales"),
+                },
+                Change::Rename {
+                    from: String::from("Engineering"),
+                    to: String::from("R&D"),
+                },
+            ];
+            change(&mut *storage, &mut directory, &moved);
+
+            assert_eq!(directory, open(&path).load().unwrap(), "{}", path.display());
+        }
+    }
+
+    #[test]
+    fn unfinished_log_line_is_dropped() {
+        let path = temp_path("torn.log");
+        let mut log = LogFile::new(&path);
+        let mut directory = Directory::new();
+        change(&mut log, &mut directory, &[add("Sally", "Engineering")]);
+
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"[{\"op\":\"add\",\"name\":\"Am").unwrap();
+
+        let mut reopened = LogFile::new(&path);
+        assert_eq!(directory, reopened.load().unwrap());
+        change(&mut reopened, &mut directory, &[add("Amir", "Sales")]);
+        assert_eq!(directory, LogFile::new(&path).load().unwrap());
+    }
+
+    #[test]
+    fn log_that_breaks_the_rules_is_invalid() {
+        let path = temp_path("invalid.log");
+        let twice = serde_json::to_string(&[add("Sally", "Sales"), add("sally", "R&D")]).unwrap();
+        fs::write(&path, twice + "\n").unwrap();
+
+        let error = LogFile::new(&path).load().unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidData, error.kind());
+        assert!(error
+            .to_string()
+            .ends_with(":1: 'Sally' already works in Sales"));
+    }
+
+    #[test]
+    fn log_is_compacted() {
+        let path = temp_path("compacted.log");
+        let mut log = LogFile::new(&path);
+        let mut directory = Directory::new();
+        for i in 0..LogFile::MIN_ENTRIES + 1 {
+            change(
+                &mut log,
+                &mut directory,
+                &[add(&format!("Person {i}"), "Sales")],
+            );
+            change(
+                &mut log,
+                &mut directory,
+                &[Change::Remove {
+                    name: format!("Person {i}"),
+                    department: String::from("Sales"),
+                }],
+            );
+        }
+
+        let lines = fs::read_to_string(&path).unwrap().lines().count();
+        assert!(lines < LogFile::MIN_ENTRIES, "{lines} lines");
+        assert_eq!(directory, LogFile::new(&path).load().unwrap());
+    }
+}
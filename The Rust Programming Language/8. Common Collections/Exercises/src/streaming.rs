@@ -0,0 +1,545 @@
+//! Statistics that are updated one value at a time, for data that doesn't
+//! fit in memory. Every accumulator can be merged with another, so chunks of
+//! the data can be summarized in parallel and combined afterwards.
+
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use std::hash::Hash;
+
+/// Combines the state of two accumulators that saw different values.
+pub trait Merge {
+    fn merge(&mut self, other: &Self);
+}
+
+/// Count, mean, variance and range, using Welford's algorithm so the
+/// variance stays accurate even when the values are large.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Moments {
+    count: u64,
+    mean: f64,
+    /// The sum of squared distances from the mean.
+    m2: f64,
+    min: f64,
+    max: f64,
+}
+
+impl Default for Moments {
+    fn default() -> Moments {
+        Moments {
+            count: 0,
+            mean: 0.0,
+            m2: 0.0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        }
+    }
+}
+
+impl Moments {
+    pub fn new() -> Moments {
+        Moments::default()
+    }
+
+    pub fn push(&mut self, value: impl Into<f64>) {
+        let value = value.into();
+        self.count += 1;
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (value - self.mean);
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn mean(&self) -> Option<f64> {
+        (self.count > 0).then_some(self.mean)
+    }
+
+    /// The population variance.
+    pub fn variance(&self) -> Option<f64> {
+        (self.count > 0).then(|| self.m2 / self.count as f64)
+    }
+
+    /// The sample variance, dividing by `n - 1`. Needs at least two values.
+    pub fn sample_variance(&self) -> Option<f64> {
+        (self.count > 1).then(|| self.m2 / (self.count - 1) as f64)
+    }
+
+    pub fn std_dev(&self) -> Option<f64> {
+        self.variance().map(f64::sqrt)
+    }
+
+    pub fn min(&self) -> Option<f64> {
+        (self.count > 0).then_some(self.min)
+    }
+
+    pub fn max(&self) -> Option<f64> {
+        (self.count > 0).then_some(self.max)
+    }
+}
+
+impl Merge for Moments {
+    /// Chan et al.'s parallel form of Welford's update.
+    fn merge(&mut self, other: &Moments) {
+        if other.count == 0 {
+            return;
+        }
+        if self.count == 0 {
+            *self = *other;
+            return;
+        }
+
+        let count = self.count + other.count;
+        let delta = other.mean - self.mean;
+        let weight = other.count as f64 / count as f64;
+        self.mean += delta * weight;
+        self.m2 += other.m2 + delta * delta * self.count as f64 * weight;
+        self.count = count;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+    }
+}
+
+impl<T: Into<f64>> Extend<T> for Moments {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, values: I) {
+        for value in values {
+            self.push(value);
+        }
+    }
+}
+
+impl<T: Into<f64>> FromIterator<T> for Moments {
+    fn from_iter<I: IntoIterator<Item = T>>(values: I) -> Moments {
+        let mut moments = Moments::new();
+        moments.extend(values);
+        moments
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Centroid {
+    mean: f64,
+    weight: f64,
+}
+
+/// Approximate quantiles in bounded memory, using a merging t-digest.
+///
+/// Values are grouped into centroids that stay small near the tails, so
+/// extreme quantiles are estimated more precisely than the median.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TDigest {
+    /// Higher keeps more centroids and gives more accurate quantiles.
+    compression: f64,
+    centroids: Vec<Centroid>,
+    buffer: Vec<f64>,
+    count: u64,
+    min: f64,
+    max: f64,
+}
+
+impl Default for TDigest {
+    fn default() -> TDigest {
+        TDigest::new(100.0)
+    }
+}
+
+impl TDigest {
+    pub fn new(compression: f64) -> TDigest {
+        TDigest {
+            compression,
+            centroids: Vec::new(),
+            buffer: Vec::new(),
+            count: 0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        }
+    }
+
+    pub fn push(&mut self, value: impl Into<f64>) {
+        let value = value.into();
+        self.buffer.push(value);
+        self.count += 1;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+
+        if self.buffer.len() as f64 >= 5.0 * self.compression {
+            self.compress();
+        }
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Estimates the value below which a `q` share of the values fall, or
+    /// `None` if there are no values or `q` is not between 0 and 1.
+    pub fn quantile(&mut self, q: f64) -> Option<f64> {
+        if self.count == 0 || !(0.0..=1.0).contains(&q) {
+            return None;
+        }
+        self.compress();
+
+        let centroids = &self.centroids;
+        let target = q * self.count as f64;
+        let first = centroids[0];
+        let last = centroids[centroids.len() - 1];
+
+        // Each centroid's mean sits at the middle of its weight; interpolate
+        // between neighbouring middles, and out to the extremes at the ends.
+        if target <= first.weight / 2.0 {
+            return Some(lerp(self.min, first.mean, target / (first.weight / 2.0)));
+        }
+
+        let mut cumulative = 0.0;
+        for pair in centroids.windows(2) {
+            let left = cumulative + pair[0].weight / 2.0;
+            let right = cumulative + pair[0].weight + pair[1].weight / 2.0;
+            if target <= right {
+                let fraction = (target - left) / (right - left);
+                return Some(lerp(pair[0].mean, pair[1].mean, fraction));
+            }
+            cumulative += pair[0].weight;
+        }
+
+        let left = self.count as f64 - last.weight / 2.0;
+        let fraction = (target - left) / (last.weight / 2.0);
+        Some(lerp(last.mean, self.max, fraction))
+    }
+
+    /// The k₁ scale function, which limits how much weight a centroid may
+    /// hold at each quantile.
+    fn scale(&self, q: f64) -> f64 {
+        self.compression / (2.0 * PI) * (2.0 * q - 1.0).asin()
+    }
+
+    fn scale_inverse(&self, k: f64) -> f64 {
+        ((k * 2.0 * PI / self.compression).sin() + 1.0) / 2.0
+    }
+
+    fn compress(&mut self) {
+        if !self.buffer.is_empty() {
+            self.rebuild();
+        }
+    }
+
+    /// Merges the buffered values and the centroids into as few centroids
+    /// as the scale function allows.
+    fn rebuild(&mut self) {
+        let mut all = std::mem::take(&mut self.centroids);
+        all.extend(
+            self.buffer
+                .drain(..)
+                .map(|mean| Centroid { mean, weight: 1.0 }),
+        );
+        if all.is_empty() {
+            return;
+        }
+        all.sort_by(|a, b| a.mean.total_cmp(&b.mean));
+
+        let total: f64 = all.iter().map(|centroid| centroid.weight).sum();
+        let mut merged = Vec::new();
+        let mut current = all[0];
+        let mut q_before = 0.0;
+        let mut q_limit = self.scale_inverse(self.scale(q_before) + 1.0);
+
+        for &next in &all[1..] {
+            let q = q_before + (current.weight + next.weight) / total;
+            if q <= q_limit {
+                let weight = current.weight + next.weight;
+                current.mean += (next.mean - current.mean) * next.weight / weight;
+                current.weight = weight;
+            } else {
+                q_before += current.weight / total;
+                q_limit = self.scale_inverse(self.scale(q_before) + 1.0);
+                merged.push(current);
+                current = next;
+            }
+        }
+        merged.push(current);
+
+        self.centroids = merged;
+    }
+}
+
+fn lerp(from: f64, to: f64, fraction: f64) -> f64 {
+    from + (to - from) * fraction.clamp(0.0, 1.0)
+}
+
+impl Merge for TDigest {
+    fn merge(&mut self, other: &TDigest) {
+        self.centroids.extend(&other.centroids);
+        self.buffer.extend(&other.buffer);
+        self.count += other.count;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        self.rebuild();
+    }
+}
+
+impl<T: Into<f64>> Extend<T> for TDigest {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, values: I) {
+        for value in values {
+            self.push(value);
+        }
+    }
+}
+
+impl<T: Into<f64>> FromIterator<T> for TDigest {
+    fn from_iter<I: IntoIterator<Item = T>>(values: I) -> TDigest {
+        let mut digest = TDigest::default();
+        digest.extend(values);
+        digest
+    }
+}
+
+/// Finds the most frequent items with the Misra-Gries algorithm, keeping at
+/// most `capacity` counters.
+///
+/// Every item that makes up more than `1 / (capacity + 1)` of the stream is
+/// among the candidates, and each count is at most `count / (capacity + 1)`
+/// below the true one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeavyHitters<T: Eq + Hash> {
+    capacity: usize,
+    counters: HashMap<T, u64>,
+    count: u64,
+}
+
+impl<T: Eq + Hash + Clone> HeavyHitters<T> {
+    pub fn new(capacity: usize) -> HeavyHitters<T> {
+        assert!(capacity > 0, "HeavyHitters needs at least one counter");
+        HeavyHitters {
+            capacity,
+            counters: HashMap::new(),
+            count: 0,
+        }
+    }
+
+    pub fn push(&mut self, item: T) {
+        self.count += 1;
+
+        if let Some(counter) = self.counters.get_mut(&item) {
+            *counter += 1;
+        } else if self.counters.len() < self.capacity {
+            self.counters.insert(item, 1);
+        } else {
+            self.counters.retain(|_, counter| {
+                *counter -= 1;
+                *counter > 0
+            });
+        }
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// The items that may be frequent with their estimated counts, most
+    /// frequent first.
+    pub fn candidates(&self) -> Vec<(T, u64)> {
+        let mut candidates: Vec<(T, u64)> = self
+            .counters
+            .iter()
+            .map(|(item, &counter)| (item.clone(), counter))
+            .collect();
+        candidates.sort_by_key(|&(_, counter)| Reverse(counter));
+        candidates
+    }
+
+    /// The estimated mode, if anything has been seen. Only reliable when the
+    /// mode really is a heavy hitter.
+    pub fn mode(&self) -> Option<T> {
+        self.candidates().into_iter().next().map(|(item, _)| item)
+    }
+}
+
+impl<T: Eq + Hash + Clone> Merge for HeavyHitters<T> {
+    /// Adds up the counters, then subtracts the `capacity + 1`-th largest
+    /// from all of them so at most `capacity` remain, which keeps the same
+    /// error bound as a single pass.
+    fn merge(&mut self, other: &HeavyHitters<T>) {
+        self.count += other.count;
+        for (item, &counter) in &other.counters {
+            *self.counters.entry(item.clone()).or_insert(0) += counter;
+        }
+
+        if self.counters.len() > self.capacity {
+            let mut counts: Vec<u64> = self.counters.values().copied().collect();
+            counts.sort_unstable_by(|a, b| b.cmp(a));
+            let cut = counts[self.capacity];
+            self.counters.retain(|_, counter| {
+                *counter = counter.saturating_sub(cut);
+                *counter > 0
+            });
+        }
+    }
+}
+
+impl<T: Eq + Hash + Clone> Extend<T> for HeavyHitters<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, items: I) {
+        for item in items {
+            self.push(item);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    fn assert_close(expected: f64, actual: f64, tolerance: f64) {
+        assert!(
+            (expected - actual).abs() <= tolerance,
+            "expected {expected} ± {tolerance}, got {actual}"
+        );
+    }
+
+    /// Summarizes each chunk on its own thread and merges the results.
+    fn merged<A: Merge + Send>(
+        values: &[i32],
+        chunks: usize,
+        new: impl Fn() -> A + Sync,
+        push: impl Fn(&mut A, i32) + Sync,
+    ) -> A {
+        let parts: Vec<A> = thread::scope(|scope| {
+            let handles: Vec<_> = values
+                .chunks(values.len().div_ceil(chunks))
+                .map(|chunk| {
+                    let (new, push) = (&new, &push);
+                    scope.spawn(move || {
+                        let mut part = new();
+                        for &value in chunk {
+                            push(&mut part, value);
+                        }
+                        part
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect()
+        });
+
+        let mut total = new();
+        for part in &parts {
+            total.merge(part);
+        }
+        total
+    }
+
+    #[test]
+    fn moments() {
+        let moments: Moments = [2, 4, 4, 4, 5, 5, 7, 9].into_iter().collect();
+        assert_eq!(8, moments.count());
+        assert_eq!(Some(5.0), moments.mean());
+        assert_eq!(Some(4.0), moments.variance());
+        assert_eq!(Some(2.0), moments.std_dev());
+        assert_eq!(Some(32.0 / 7.0), moments.sample_variance());
+        assert_eq!((Some(2.0), Some(9.0)), (moments.min(), moments.max()));
+
+        let empty = Moments::new();
+        assert_eq!(None, empty.mean());
+        assert_eq!(None, empty.max());
+    }
+
+    #[test]
+    fn moments_stay_accurate_for_large_values() {
+        let offset = 1e9;
+        let moments: Moments = [4.0, 7.0, 13.0, 16.0]
+            .into_iter()
+            .map(|value| value + offset)
+            .collect();
+        assert_close(30.0, moments.sample_variance().unwrap(), 1e-6);
+    }
+
+    #[test]
+    fn merged_moments_match_one_pass() {
+        let values: Vec<i32> = (0..1000).map(|i| (i * 37) % 101).collect();
+        let one_pass: Moments = values.iter().copied().collect();
+        let parallel: Moments = merged(&values, 4, Moments::new, |moments: &mut Moments, value| {
+            moments.push(value)
+        });
+
+        assert_eq!(one_pass.count(), parallel.count());
+        assert_close(one_pass.mean().unwrap(), parallel.mean().unwrap(), 1e-9);
+        assert_close(
+            one_pass.variance().unwrap(),
+            parallel.variance().unwrap(),
+            1e-9,
+        );
+        assert_eq!(one_pass.min(), parallel.min());
+
+        let mut empty = Moments::new();
+        empty.merge(&one_pass);
+        assert_eq!(one_pass, empty);
+    }
+
+    #[test]
+    fn digest_is_exact_for_few_values() {
+        let mut digest: TDigest = [5, 1, 4, 2, 3].into_iter().collect();
+        assert_eq!(Some(1.0), digest.quantile(0.0));
+        assert_eq!(Some(3.0), digest.quantile(0.5));
+        assert_eq!(Some(5.0), digest.quantile(1.0));
+        assert_eq!(None, digest.quantile(1.5));
+        assert_eq!(None, TDigest::default().quantile(0.5));
+    }
+
+    #[test]
+    fn digest_estimates_quantiles() {
+        let values: Vec<i32> = (1..=100_000).map(|i| (i * 7919) % 100_000).collect();
+        let mut digest: TDigest = values.iter().copied().collect();
+
+        assert!(digest.centroids.len() < 200);
+        assert_close(50_000.0, digest.quantile(0.5).unwrap(), 500.0);
+        assert_close(99_000.0, digest.quantile(0.99).unwrap(), 100.0);
+        assert_close(1_000.0, digest.quantile(0.01).unwrap(), 100.0);
+    }
+
+    #[test]
+    fn merged_digests_estimate_quantiles() {
+        let values: Vec<i32> = (0..100_000).map(|i| (i * 7919) % 100_000).collect();
+        let mut digest: TDigest = merged(
+            &values,
+            8,
+            TDigest::default,
+            |digest: &mut TDigest, value| digest.push(value),
+        );
+
+        assert_eq!(100_000, digest.count());
+        assert_close(25_000.0, digest.quantile(0.25).unwrap(), 500.0);
+        assert_close(90_000.0, digest.quantile(0.9).unwrap(), 500.0);
+    }
+
+    #[test]
+    fn heavy_hitters_find_frequent_items() {
+        let mut hitters = HeavyHitters::new(2);
+        hitters.extend("abacabadabacaba".chars());
+
+        // 'a' is 8 of 15, more than a third, so it must be found.
+        assert_eq!(Some('a'), hitters.mode());
+        let (_, estimate) = hitters.candidates()[0];
+        assert!((8 - 15 / 3..=8).contains(&estimate));
+        assert_eq!(15, hitters.count());
+    }
+
+    #[test]
+    fn merged_heavy_hitters() {
+        let values: Vec<i32> = (0..3000).map(|i| if i % 3 == 0 { 7 } else { i }).collect();
+        let hitters: HeavyHitters<i32> = merged(
+            &values,
+            3,
+            || HeavyHitters::new(10),
+            |hitters: &mut HeavyHitters<i32>, value| hitters.push(value),
+        );
+
+        assert_eq!(Some(7), hitters.mode());
+        assert!(hitters.candidates().len() <= 10);
+        assert_eq!(3000, hitters.count());
+    }
+}
@@ -0,0 +1,285 @@
+//! Word-by-word text transformations. Text is split into words, whitespace
+//! and punctuation, and only the words are changed, so everything else comes
+//! out exactly as it went in.
+
+use crate::pig_latin;
+
+/// A piece of text, as split up by [`tokenize`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Token<'a> {
+    /// Letters and digits, with any apostrophes between them.
+    Word(&'a str),
+    Whitespace(&'a str),
+    Punctuation(&'a str),
+}
+
+impl<'a> Token<'a> {
+    pub fn as_str(&self) -> &'a str {
+        match self {
+            Token::Word(text) | Token::Whitespace(text) | Token::Punctuation(text) => text,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Word,
+    Whitespace,
+    Punctuation,
+}
+
+impl Kind {
+    fn token(self, text: &str) -> Token<'_> {
+        match self {
+            Kind::Word => Token::Word(text),
+            Kind::Whitespace => Token::Whitespace(text),
+            Kind::Punctuation => Token::Punctuation(text),
+        }
+    }
+}
+
+/// Splits `text` into tokens which, joined back together, give `text`.
+pub fn tokenize(text: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut current: Option<(Kind, usize)> = None;
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        let in_word = matches!(current, Some((Kind::Word, _)));
+        let next_is_alphanumeric = chars
+            .peek()
+            .is_some_and(|&(_, next)| next.is_alphanumeric());
+        let kind = match c {
+            c if c.is_alphanumeric() => Kind::Word,
+            '\'' | '’' if in_word && next_is_alphanumeric => Kind::Word,
+            c if c.is_whitespace() => Kind::Whitespace,
+            _ => Kind::Punctuation,
+        };
+
+        match current {
+            Some((previous, _)) if previous == kind => {}
+            Some((previous, start)) => {
+                tokens.push(previous.token(&text[start..i]));
+                current = Some((kind, i));
+            }
+            None => current = Some((kind, i)),
+        }
+    }
+    if let Some((kind, start)) = current {
+        tokens.push(kind.token(&text[start..]));
+    }
+    tokens
+}
+
+/// Changes one word at a time.
+pub trait WordTransform {
+    fn transform(&self, word: &str) -> String;
+
+    /// Transforms every word of `text`, keeping whitespace and punctuation.
+    fn apply(&self, text: &str) -> String {
+        tokenize(text)
+            .into_iter()
+            .map(|token| match token {
+                Token::Word(word) => self.transform(word),
+                other => other.as_str().to_string(),
+            })
+            .collect()
+    }
+}
+
+/// "string" becomes "ing-stray", see [`pig_latin`].
+pub struct PigLatin;
+
+impl WordTransform for PigLatin {
+    fn transform(&self, word: &str) -> String {
+        pig_latin::encode_word(word)
+    }
+}
+
+/// "ub" goes before every vowel sound, so "hello" becomes "hubellubo". A
+/// silent "e" at the end of a word is left alone.
+pub struct UbbiDubbi;
+
+impl WordTransform for UbbiDubbi {
+    fn transform(&self, word: &str) -> String {
+        let chars: Vec<char> = word.chars().collect();
+        let silent_e = chars.len() > 2
+            && chars[chars.len() - 1].eq_ignore_ascii_case(&'e')
+            && !pig_latin::is_vowel(chars[chars.len() - 2])
+            && chars[..chars.len() - 2]
+                .iter()
+                .any(|&c| pig_latin::is_vowel(c));
+
+        let mut transformed = String::with_capacity(word.len() * 2);
+        for (i, &c) in chars.iter().enumerate() {
+            let starts_sound =
+                pig_latin::is_vowel(c) && (i == 0 || !pig_latin::is_vowel(chars[i - 1]));
+            if starts_sound && !(silent_e && i == chars.len() - 1) {
+                if i == 0 && c.is_uppercase() {
+                    // "Apple" becomes "Ubapple", not "ubApple".
+                    transformed.push_str("Ub");
+                    transformed.extend(c.to_lowercase());
+                    continue;
+                }
+                transformed.push_str("ub");
+            }
+            transformed.push(c);
+        }
+        transformed
+    }
+}
+
+/// Spells each word backwards.
+pub struct Reverse;
+
+impl WordTransform for Reverse {
+    fn transform(&self, word: &str) -> String {
+        word.chars().rev().collect()
+    }
+}
+
+/// Swaps letters for the digits that look like them.
+pub struct Leetspeak;
+
+impl WordTransform for Leetspeak {
+    fn transform(&self, word: &str) -> String {
+        word.chars()
+            .map(|c| match c.to_ascii_lowercase() {
+                'a' => '4',
+                'e' => '3',
+                'i' => '1',
+                'o' => '0',
+                's' => '5',
+                't' => '7',
+                _ => c,
+            })
+            .collect()
+    }
+}
+
+/// Rotates ASCII letters 13 places, so applying it twice gives the word back.
+pub struct Rot13;
+
+impl WordTransform for Rot13 {
+    fn transform(&self, word: &str) -> String {
+        word.chars()
+            .map(|c| match c {
+                'a'..='z' => ((c as u8 - b'a' + 13) % 26 + b'a') as char,
+                'A'..='Z' => ((c as u8 - b'A' + 13) % 26 + b'A') as char,
+                _ => c,
+            })
+            .collect()
+    }
+}
+
+/// Looks a transform up by the name the command line uses for it.
+pub fn named(name: &str) -> Option<Box<dyn WordTransform>> {
+    match name {
+        "pig-latin" => Some(Box::new(PigLatin)),
+        "ubbi-dubbi" => Some(Box::new(UbbiDubbi)),
+        "reverse" => Some(Box::new(Reverse)),
+        "leet" => Some(Box::new(Leetspeak)),
+        "rot13" => Some(Box::new(Rot13)),
+        _ => None,
+    }
+}
+
+/// Transforms applied one after another, each to the previous one's result.
+#[derive(Default)]
+pub struct Pipeline {
+    transforms: Vec<Box<dyn WordTransform>>,
+}
+
+impl Pipeline {
+    pub fn new() -> Pipeline {
+        Pipeline::default()
+    }
+
+    pub fn then(mut self, transform: impl WordTransform + 'static) -> Pipeline {
+        self.transforms.push(Box::new(transform));
+        self
+    }
+
+    pub fn push(&mut self, transform: Box<dyn WordTransform>) {
+        self.transforms.push(transform);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.transforms.is_empty()
+    }
+}
+
+impl WordTransform for Pipeline {
+    fn transform(&self, word: &str) -> String {
+        self.transforms
+            .iter()
+            .fold(word.to_string(), |word, transform| {
+                transform.transform(&word)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Token::*;
+
+    #[test]
+    fn tokens_round_trip() {
+        let text = "  Don't stop—it's 42 o'clock!\n\t'quoted' ... ünïcödé ";
+        let tokens = tokenize(text);
+        assert_eq!(text, tokens.iter().map(Token::as_str).collect::<String>());
+        assert_eq!(
+            vec![
+                Whitespace("  "),
+                Word("Don't"),
+                Whitespace(" "),
+                Word("stop"),
+                Punctuation("—"),
+                Word("it's"),
+                Whitespace(" "),
+                Word("42"),
+                Whitespace(" "),
+                Word("o'clock"),
+                Punctuation("!"),
+                Whitespace("\n\t"),
+                Punctuation("'"),
+                Word("quoted"),
+                Punctuation("'"),
+                Whitespace(" "),
+                Punctuation("..."),
+                Whitespace(" "),
+                Word("ünïcödé"),
+                Whitespace(" "),
+            ],
+            tokens
+        );
+        assert_eq!(Vec::<Token>::new(), tokenize(""));
+    }
+
+    #[test]
+    fn transforms() {
+        assert_eq!("Ing-stray, apple-hay!", PigLatin.apply("String, apple!"));
+        assert_eq!("hubellubo, wuborld", UbbiDubbi.apply("hello, world"));
+        assert_eq!("Ubapple lubike", UbbiDubbi.apply("Apple like"));
+        assert_eq!("olleh, dlrow", Reverse.apply("hello, world"));
+        assert_eq!("L337 5p34k", Leetspeak.apply("Leet speak"));
+        assert_eq!("Uryyb, Jbeyq!", Rot13.apply("Hello, World!"));
+        assert_eq!("Hello, World!", Rot13.apply(&Rot13.apply("Hello, World!")));
+    }
+
+    #[test]
+    fn pipelines() {
+        let pipeline = Pipeline::new().then(PigLatin).then(Rot13);
+        assert_eq!("Vat-fgenl (nccyr-unl)", pipeline.apply("String (apple)"));
+
+        let mut named_pipeline = Pipeline::new();
+        for name in ["reverse", "leet"] {
+            named_pipeline.push(named(name).unwrap());
+        }
+        assert_eq!("0ll3h w0rld", named_pipeline.apply("hello dlrow"));
+        assert!(named("klingon").is_none());
+
+        assert_eq!("as is", Pipeline::new().apply("as is"));
+    }
+}
@@ -0,0 +1,74 @@
+//! The `transform` command: applies a pipeline of word transforms to stdin.
+
+use std::error::Error;
+use std::io::{self, BufRead, Write};
+
+use crate::transform::{self, Pipeline, WordTransform};
+
+pub struct Config {
+    pub pipeline: Pipeline,
+}
+
+impl Config {
+    /// Each argument names a transform, applied in the order given.
+    pub fn build(mut args: impl Iterator<Item = String>) -> Result<Config, &'static str> {
+        args.next();
+
+        let mut pipeline = Pipeline::new();
+        for name in args {
+            let transform = transform::named(&name).ok_or(
+                "Unknown transform, expected pig-latin, ubbi-dubbi, reverse, leet or rot13",
+            )?;
+            pipeline.push(transform);
+        }
+
+        if pipeline.is_empty() {
+            return Err("Didn't get any transforms");
+        }
+        Ok(Config { pipeline })
+    }
+}
+
+/// Transforms `input` a line at a time, so output keeps up with typing.
+pub fn run_to(
+    config: &Config,
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+) -> Result<(), Box<dyn Error>> {
+    let mut line = String::new();
+    while input.read_line(&mut line)? > 0 {
+        write!(output, "{}", config.pipeline.apply(&line))?;
+        output.flush()?;
+        line.clear();
+    }
+    Ok(())
+}
+
+pub fn run(config: Config) -> Result<(), Box<dyn Error>> {
+    run_to(&config, &mut io::stdin().lock(), &mut io::stdout())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(args: &[&str]) -> Result<Config, &'static str> {
+        let args = ["transform"].iter().chain(args).map(|arg| arg.to_string());
+        Config::build(args)
+    }
+
+    #[test]
+    fn arguments() {
+        assert!(config(&["pig-latin", "rot13"]).is_ok());
+        assert!(config(&[]).is_err());
+        assert!(config(&["pig-latin", "klingon"]).is_err());
+    }
+
+    #[test]
+    fn lines_keep_their_endings() {
+        let config = config(&["reverse"]).unwrap();
+        let mut output = Vec::new();
+        run_to(&config, &mut "one two\r\n\n  three".as_bytes(), &mut output).unwrap();
+        assert_eq!("eno owt\r\n\n  eerht", String::from_utf8(output).unwrap());
+    }
+}
@@ -0,0 +1,73 @@
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+fn stats(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_stats"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to start stats");
+
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn summary_from_stdin_with_invalid_lines() {
+    let output = stats(&[], "3\n1\noops\n2 2\n");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert!(output.status.success());
+    assert!(stdout.starts_with("Count: 4\nMin: 1\nMax: 3\nMean: 2\nMedian: 2\n"));
+    assert!(stdout.ends_with("Invalid lines: 1\n"));
+    assert_eq!("<stdin>:3: 'oops' is not a number\n", stderr);
+}
+
+#[test]
+fn json_with_histogram_from_csv_file() {
+    let dir = env::temp_dir().join(format!("stats_cli-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("prices.csv");
+    fs::write(&path, "item,price\napple,1\npear,3\nfig,2\n").unwrap();
+
+    let args = [
+        path.to_str().unwrap(),
+        "--column",
+        "price",
+        "--format",
+        "json",
+        "--histogram",
+        "2",
+    ];
+    let output = stats(&args, "");
+    fs::remove_dir_all(&dir).unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(output.status.success());
+    assert!(stdout.starts_with("{\n  \"count\": 3,\n"));
+    assert!(stdout.contains("\"histogram\": [{\"start\": 1, \"end\": 2, \"count\": 1}, "));
+    assert!(stdout.ends_with("  \"invalid_lines\": 0\n}\n"));
+}
+
+#[test]
+fn errors_exit_with_failure() {
+    let output = stats(&[], "nothing here\n");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.ends_with("Application error: no numbers to summarize\n"));
+
+    let output = stats(&["--histogram"], "");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.starts_with("Problem parsing arguments: "));
+}
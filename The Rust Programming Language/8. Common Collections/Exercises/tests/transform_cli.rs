@@ -0,0 +1,38 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+fn transform(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_transform"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to start transform");
+
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn pipeline_over_stdin() {
+    let output = transform(&["pig-latin", "rot13"], "String theory,\n  apple pie!\n");
+    assert!(output.status.success());
+    assert_eq!(
+        "Vat-fgenl rbel-gunl,\n  nccyr-unl vr-cnl!\n",
+        String::from_utf8(output.stdout).unwrap()
+    );
+}
+
+#[test]
+fn unknown_transform() {
+    let output = transform(&["reverse", "klingon"], "");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.starts_with("Problem parsing arguments: Unknown transform"));
+}